@@ -7,15 +7,23 @@ use libc::{
   c_ushort,
 };
 
-use ::display::Xid;
+use ::display::{
+  Connection,
+  Xid,
+};
 use ::internal::{
   FieldMask,
   FromNative,
   ToNative,
 };
+use ::owned::{
+  Owned,
+  Resource,
+};
 
 /** Colormap identifier type. */
-pub type Colormap = Xid;
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Colormap(pub Xid);
 
 
 //
@@ -64,3 +72,20 @@ impl ToNative<::ffi::XColor> for Color {
     }
   }
 }
+
+
+//
+// OwnedColormap
+//
+
+
+/** A colormap that is freed when dropped. */
+pub type OwnedColormap = Owned<Colormap>;
+
+impl Resource for Colormap {
+  fn free (self, connection: &Connection) {
+    unsafe {
+      ::ffi::XFreeColormap(connection.to_native(), self.0 as c_ulong);
+    }
+  }
+}
@@ -4,4 +4,5 @@
 use ::display::Xid;
 
 /** Cursor identifier type. */
-pub type Cursor = Xid;
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Cursor(pub Xid);
@@ -1,24 +1,57 @@
 // Copyright (c) 2015, <daggerbot@gmail.com>
 // All rights reserved.
 
+use std::any::Any;
+use std::env::var;
 use std::ffi::CString;
+use std::io::ErrorKind;
+use std::net::{
+  SocketAddr,
+  TcpStream,
+  ToSocketAddrs,
+};
+use std::os::unix::net::UnixStream;
 use std::mem::zeroed;
+use std::ops::{
+  Deref,
+  DerefMut,
+};
+use std::panic::{
+  catch_unwind,
+  resume_unwind,
+  AssertUnwindSafe,
+};
 use std::ptr::{
   null,
   null_mut,
 };
+use std::sync::{
+  Arc,
+  Mutex,
+  MutexGuard,
+};
 use std::sync::atomic::{
   AtomicBool,
   Ordering,
   ATOMIC_BOOL_INIT,
 };
+use std::time::{
+  Duration,
+  Instant,
+};
 
 use libc::{
   c_char,
   c_int,
+  c_long,
+  c_short,
+  c_uchar,
   c_uint,
   c_ulong,
   c_void,
+  poll,
+  pollfd,
+  POLLIN,
 };
 
 use ::colormap::{
@@ -29,9 +62,16 @@ use ::drawable::{
   Drawable,
   Geometry,
 };
+use ::error::{
+  IoError,
+  OpenError,
+  XError,
+};
 use ::event::{
   Event,
   EventMask,
+  EventType,
+  KeyEvent,
 };
 use ::gc::{
   ClipOrdering,
@@ -40,27 +80,114 @@ use ::gc::{
   Rectangle,
 };
 use ::internal::{
+  latin1_to_string,
+  string_to_latin1,
   FieldMask,
   FromNative,
   ToNative,
 };
+use ::keysym::Keysym;
+use ::owned::Owned;
+use ::pixmap::{
+  Pixmap,
+  PixmapFormat,
+};
+use ::property::{
+  Property,
+  PropertyData,
+  PropertyMode,
+  PropertyWatch,
+  TextEncoding,
+};
+use ::screen::Screen;
 use ::visual::{
   Visual,
   VisualInfo,
   VisualTemplate,
 };
 use ::window::{
+  CirculateDirection,
+  ClassHint,
   SetWindowAttributes,
   SizeHints,
+  SizeHintsMask,
   Window,
   WindowAttributes,
+  WindowChanges,
   WindowClass,
+  WindowTree,
+  WmHints,
 };
 
 // resource identifier types
-pub type Atom = Xid;
 pub type Xid = u32;
 
+// server timestamp type
+pub type Time = u32;
+
+// number of 32-bit units requested per XGetWindowProperty call
+const PROPERTY_CHUNK_LENGTH: c_long = 1024;
+
+
+//
+// Atom
+//
+
+
+/** Atom identifier type. */
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Atom(pub Xid);
+
+
+//
+// ByteOrder
+//
+
+
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub enum ByteOrder {
+  LsbFirst,
+  MsbFirst,
+}
+
+impl FromNative<c_int> for Option<ByteOrder> {
+  fn from_native (num: c_int) -> Option<ByteOrder> {
+    match num {
+      0 => Some(ByteOrder::LsbFirst),
+      1 => Some(ByteOrder::MsbFirst),
+      _ => None,
+    }
+  }
+}
+
+
+//
+// Connection
+//
+
+
+/** Keeps a server connection open. Shared by a `Display` and the resource handles created from it, so the
+ * connection is only closed once all of them are gone. */
+pub struct Connection {
+  ptr: *mut ::ffi::Display,
+}
+
+impl Drop for Connection {
+  fn drop (&mut self) {
+    unsafe {
+      ::ffi::XCloseDisplay(self.ptr);
+      lock(&LOST_CONNECTIONS).retain(|&(display, _)| display != self.ptr as usize);
+      lock(&AFTER_STATES).retain(|state| state.display != self.ptr as usize);
+    }
+  }
+}
+
+impl ToNative<*mut ::ffi::Display> for Connection {
+  fn to_native (&self) -> *mut ::ffi::Display {
+    self.ptr
+  }
+}
+
 
 //
 // Display
@@ -69,19 +196,43 @@ pub type Xid = u32;
 
 pub struct Display {
   ptr: *mut ::ffi::Display,
+  connection: Arc<Connection>,
 }
 
 impl Display {
   pub fn alloc_color (&mut self, colormap: Colormap, color: Color) -> Option<Color> {
     unsafe {
       let mut xcolor = color.to_native();
-      if ::ffi::XAllocColor(self.ptr, colormap as c_ulong, &mut xcolor) == 0 {
+      if ::ffi::XAllocColor(self.ptr, colormap.0 as c_ulong, &mut xcolor) == 0 {
         return None;
       }
       return Some(FromNative::from_native(xcolor));
     }
   }
 
+  pub fn bitmap_bit_order (&mut self) -> ByteOrder {
+    unsafe {
+      if let Some(order) = FromNative::from_native(::ffi::XBitmapBitOrder(self.ptr)) {
+        return order;
+      } else {
+        error!("XBitmapBitOrder returned invalid data");
+        return ByteOrder::LsbFirst;
+      }
+    }
+  }
+
+  pub fn bitmap_pad (&mut self) -> i32 {
+    unsafe {
+      return ::ffi::XBitmapPad(self.ptr) as i32;
+    }
+  }
+
+  pub fn bitmap_unit (&mut self) -> i32 {
+    unsafe {
+      return ::ffi::XBitmapUnit(self.ptr) as i32;
+    }
+  }
+
   pub fn black_pixel (&mut self, screen_num: i32) -> u32 {
     unsafe {
       if screen_num < 0 || screen_num >= self.screen_count() {
@@ -91,29 +242,189 @@ impl Display {
     }
   }
 
+  pub fn change_property (&mut self, window: Window, property: Atom, kind: Atom, mode: PropertyMode,
+      data: &PropertyData) {
+    unsafe {
+      // Xlib expects format 32 data as an array of longs, whatever their size
+      let long_vec: Vec<c_long>;
+      let data_ptr = match *data {
+        PropertyData::Byte(ref bytes) => bytes.as_ptr(),
+        PropertyData::Short(ref shorts) => shorts.as_ptr() as *const c_uchar,
+        PropertyData::Long(ref longs) => {
+          long_vec = longs.iter().map(|long| *long as c_long).collect();
+          long_vec.as_ptr() as *const c_uchar
+        },
+      };
+      ::ffi::XChangeProperty(self.ptr, window.0 as c_ulong, property.0 as c_ulong, kind.0 as c_ulong,
+          data.format() as c_int, mode.to_native(), data_ptr, data.len() as c_int);
+    }
+  }
+
+  /** Returns an error if the connection has been lost. */
+  pub fn check_connection (&self) -> Result<(), IoError> {
+    let lost = lock(&LOST_CONNECTIONS);
+    for &(display, error) in lost.iter() {
+      if display == self.ptr as usize {
+        return Err(error);
+      }
+    }
+    return Ok(());
+  }
+
+  /** Removes and returns the first queued event matching `predicate`, without blocking. If `predicate` panics, the
+   * panic is resumed and the queue is left as it was. */
+  pub fn check_if_event<F> (&mut self, mut predicate: F) -> Option<Event>
+    where F: FnMut(&Event) -> bool
+  {
+    unsafe {
+      let mut xevent: ::ffi::XEvent = zeroed();
+      let mut state = PredicateState {
+        predicate: &mut predicate,
+        panic: None,
+        match_on_panic: false,
+      };
+      let arg = &mut state as *mut PredicateState as ::ffi::XPointer;
+      let matched = ::ffi::XCheckIfEvent(self.ptr, &mut xevent, event_predicate, arg) != 0;
+      if let Some(payload) = state.panic.take() {
+        resume_unwind(payload);
+      }
+      if !matched {
+        return None;
+      }
+      return Some(FromNative::from_native(xevent));
+    }
+  }
+
+  pub fn check_typed_event (&mut self, event_type: EventType) -> Option<Event> {
+    unsafe {
+      let mut xevent: ::ffi::XEvent = zeroed();
+      if ::ffi::XCheckTypedEvent(self.ptr, event_type.to_native(), &mut xevent) == 0 {
+        return None;
+      }
+      return Some(FromNative::from_native(xevent));
+    }
+  }
+
+  pub fn check_typed_window_event (&mut self, window: Window, event_type: EventType) -> Option<Event> {
+    unsafe {
+      let mut xevent: ::ffi::XEvent = zeroed();
+      if ::ffi::XCheckTypedWindowEvent(self.ptr, window.0 as c_ulong, event_type.to_native(), &mut xevent) == 0 {
+        return None;
+      }
+      return Some(FromNative::from_native(xevent));
+    }
+  }
+
+  pub fn check_window_event (&mut self, window: Window, event_mask: EventMask) -> Option<Event> {
+    unsafe {
+      let mut xevent: ::ffi::XEvent = zeroed();
+      if ::ffi::XCheckWindowEvent(self.ptr, window.0 as c_ulong, event_mask.to_native(), &mut xevent) == 0 {
+        return None;
+      }
+      return Some(FromNative::from_native(xevent));
+    }
+  }
+
+  pub fn circulate_subwindows (&mut self, window: Window, direction: CirculateDirection) {
+    unsafe {
+      ::ffi::XCirculateSubwindows(self.ptr, window.0 as c_ulong, direction.to_native());
+    }
+  }
+
+  pub fn clear_after_function (&mut self) {
+    self.update_after_state(|state| state.function = None);
+  }
+
+  pub fn configure_window (&mut self, window: Window, changes: WindowChanges) {
+    unsafe {
+      let xchanges = changes.to_native();
+      ::ffi::XConfigureWindow(self.ptr, window.0 as c_ulong, changes.field_mask(), &xchanges);
+    }
+  }
+
+  /** Returns a handle that keeps the connection open for as long as it is held, even after the `Display` is
+   * dropped. */
+  pub fn connection (&self) -> Arc<Connection> {
+    self.connection.clone()
+  }
+
+  /** Returns the file descriptor of the X server connection, for use with poll, select or epoll. */
+  pub fn connection_number (&self) -> i32 {
+    unsafe {
+      ::ffi::XConnectionNumber(self.ptr) as i32
+    }
+  }
+
   pub fn create_colormap (&mut self, window: Window, visual: Visual, alloc: bool) -> Colormap {
     unsafe {
-      return ::ffi::XCreateColormap(self.ptr, window as c_ulong, visual.to_native(),
-          if alloc {::ffi::AllocAll} else {::ffi::AllocNone}) as Colormap;
+      let colormap = ::ffi::XCreateColormap(self.ptr, window.0 as c_ulong, visual.to_native(),
+          if alloc {::ffi::AllocAll} else {::ffi::AllocNone});
+      return Colormap(colormap as Xid);
     }
   }
 
-  pub fn create_gc (&mut self, drawable: Drawable, values: GcValues) -> Gcid {
+  /** Like `create_colormap`, but the colormap is freed when the returned handle is dropped. */
+  pub fn create_colormap_owned (&mut self, window: Window, visual: Visual, alloc: bool) -> Owned<Colormap> {
+    let colormap = self.create_colormap(window, visual, alloc);
+    return Owned::from_raw(self, colormap);
+  }
+
+  pub fn create_gc<D> (&mut self, drawable: D, values: GcValues) -> Gcid
+    where D: Drawable
+  {
     unsafe {
       let xgcvalues = values.to_native();
-      return ::ffi::XCreateGC(self.ptr, drawable as c_ulong, values.field_mask(), &xgcvalues) as Gcid;
+      let gc = ::ffi::XCreateGC(self.ptr, drawable.drawable_id() as c_ulong, values.field_mask(), &xgcvalues);
+      return Gcid(gc as Xid);
+    }
+  }
+
+  /** Like `create_gc`, but the graphics context is freed when the returned handle is dropped. */
+  pub fn create_gc_owned<D> (&mut self, drawable: D, values: GcValues) -> Owned<Gcid>
+    where D: Drawable
+  {
+    let gc = self.create_gc(drawable, values);
+    return Owned::from_raw(self, gc);
+  }
+
+  pub fn create_pixmap<D> (&mut self, drawable: D, width: i32, height: i32, depth: i32) -> Pixmap
+    where D: Drawable
+  {
+    unsafe {
+      let pixmap = ::ffi::XCreatePixmap(self.ptr, drawable.drawable_id() as c_ulong, width as c_uint,
+          height as c_uint, depth as c_uint);
+      return Pixmap(pixmap as Xid);
     }
   }
 
+  /** Like `create_pixmap`, but the pixmap is freed when the returned handle is dropped. */
+  pub fn create_pixmap_owned<D> (&mut self, drawable: D, width: i32, height: i32, depth: i32) -> Owned<Pixmap>
+    where D: Drawable
+  {
+    let pixmap = self.create_pixmap(drawable, width, height, depth);
+    return Owned::from_raw(self, pixmap);
+  }
+
   pub fn create_simple_window (&mut self, parent: Window, x: i32, y: i32, width: i32, height: i32, border_width: i32,
       border_pixel: u32, background_pixel: u32) -> Window
   {
     unsafe {
-      return ::ffi::XCreateSimpleWindow(self.ptr, parent as c_ulong, x as c_int, y as c_int, width as c_uint,
-          height as c_uint, border_width as c_uint, border_pixel as c_ulong, background_pixel as c_ulong) as Window;
+      let window = ::ffi::XCreateSimpleWindow(self.ptr, parent.0 as c_ulong, x as c_int, y as c_int,
+          width as c_uint, height as c_uint, border_width as c_uint, border_pixel as c_ulong,
+          background_pixel as c_ulong);
+      return Window(window as Xid);
     }
   }
 
+  /** Like `create_simple_window`, but the window is destroyed when the returned handle is dropped. */
+  pub fn create_simple_window_owned (&mut self, parent: Window, x: i32, y: i32, width: i32, height: i32,
+      border_width: i32, border_pixel: u32, background_pixel: u32) -> Owned<Window>
+  {
+    let window = self.create_simple_window(parent, x, y, width, height, border_width, border_pixel,
+        background_pixel);
+    return Owned::from_raw(self, window);
+  }
+
   pub fn create_window (&mut self, parent: Window, x: i32, y: i32, width: i32, height: i32, border_width: i32,
       depth: Option<i32>, class: WindowClass, visual: Option<Visual>, attr: SetWindowAttributes) -> Window
   {
@@ -121,18 +432,27 @@ impl Display {
       let c_depth = if let Some(d) = depth {d as c_int} else {0};
       let c_visual = if let Some(v) = visual {v.to_native()} else {null()};
       let c_attr = attr.to_native();
-      return ::ffi::XCreateWindow(self.ptr, parent as c_ulong, x as c_int, y as c_int, width as c_uint,
-          height as c_uint, border_width as c_uint, c_depth, class.to_native(), c_visual, attr.field_mask(), &c_attr)
-          as Window;
+      let window = ::ffi::XCreateWindow(self.ptr, parent.0 as c_ulong, x as c_int, y as c_int, width as c_uint,
+          height as c_uint, border_width as c_uint, c_depth, class.to_native(), c_visual, attr.field_mask(), &c_attr);
+      return Window(window as Xid);
     }
   }
 
+  /** Like `create_window`, but the window is destroyed when the returned handle is dropped. */
+  pub fn create_window_owned (&mut self, parent: Window, x: i32, y: i32, width: i32, height: i32,
+      border_width: i32, depth: Option<i32>, class: WindowClass, visual: Option<Visual>,
+      attr: SetWindowAttributes) -> Owned<Window>
+  {
+    let window = self.create_window(parent, x, y, width, height, border_width, depth, class, visual, attr);
+    return Owned::from_raw(self, window);
+  }
+
   pub fn default_colormap (&mut self, screen_num: i32) -> Colormap {
     unsafe {
       if screen_num < 0 || screen_num >= self.screen_count() {
-        return 0;
+        return Colormap(0);
       }
-      return ::ffi::XDefaultColormap(self.ptr, screen_num as c_int) as Colormap;
+      return Colormap(::ffi::XDefaultColormap(self.ptr, screen_num as c_int) as Xid);
     }
   }
 
@@ -142,6 +462,16 @@ impl Display {
     }
   }
 
+  pub fn default_screen_of_display (&self) -> Option<Screen> {
+    unsafe {
+      let ptr = ::ffi::XDefaultScreenOfDisplay(self.ptr);
+      if ptr == null() {
+        return None;
+      }
+      return Some(FromNative::from_native(ptr));
+    }
+  }
+
   pub fn default_visual (&mut self, screen_num: i32) -> Visual {
     unsafe {
       if screen_num < 0 || screen_num >= self.screen_count() {
@@ -151,30 +481,62 @@ impl Display {
     }
   }
 
+  pub fn delete_property (&mut self, window: Window, property: Atom) {
+    unsafe {
+      ::ffi::XDeleteProperty(self.ptr, window.0 as c_ulong, property.0 as c_ulong);
+    }
+  }
+
   pub fn destroy_window (&mut self, window: Window) {
     unsafe {
-      return ::ffi::XDestroyWindow(self.ptr, window as c_ulong);;
+      return ::ffi::XDestroyWindow(self.ptr, window.0 as c_ulong);;
+    }
+  }
+
+  /** Returns the name the display was opened with, as passed to `open` or taken from `DISPLAY`. */
+  pub fn display_string (&mut self) -> String {
+    unsafe {
+      let name_ptr = ::ffi::XDisplayString(self.ptr);
+      if name_ptr == null_mut() {
+        return String::new();
+      }
+      let name_byte_ptr = name_ptr as *const c_char as *const u8;
+      let name_len = ::libc::strlen(name_ptr as *const c_char) as usize;
+      let name_slice = ::std::slice::from_raw_buf(&name_byte_ptr, name_len);
+      return String::from_utf8_lossy(name_slice).into_owned();
     }
   }
 
-  pub fn draw_line (&mut self, drawable: Drawable, gc: Gcid, x0: i32, y0: i32, x1: i32, y1: i32) {
+  pub fn draw_line<D> (&mut self, drawable: D, gc: Gcid, x0: i32, y0: i32, x1: i32, y1: i32)
+    where D: Drawable
+  {
     unsafe {
-      ::ffi::XDrawLine(self.ptr, drawable as c_ulong, gc as c_ulong, x0 as c_int, y0 as c_int,
+      ::ffi::XDrawLine(self.ptr, drawable.drawable_id() as c_ulong, gc.0 as c_ulong, x0 as c_int, y0 as c_int,
           x1 as c_int, y1 as c_int);
     }
   }
 
-  pub fn draw_rectangle (&mut self, drawable: Drawable, gc: Gcid, x: i32, y: i32, width: i32, height: i32) {
+  pub fn draw_rectangle<D> (&mut self, drawable: D, gc: Gcid, x: i32, y: i32, width: i32, height: i32)
+    where D: Drawable
+  {
     unsafe {
-      ::ffi::XDrawRectangle(self.ptr, drawable as c_ulong, gc as c_ulong, x as c_int, y as c_int,
+      ::ffi::XDrawRectangle(self.ptr, drawable.drawable_id() as c_ulong, gc.0 as c_ulong, x as c_int, y as c_int,
           width as c_uint, height as c_uint);
     }
   }
 
+  /** Returns an iterator that blocks for each event, like `next_event`. Ends if the connection is lost. */
+  pub fn events (&mut self) -> Events {
+    Events {
+      display: self,
+    }
+  }
+
+  /** Reads WM_NAME as Latin-1 text. Use `get_wm_name` for names in other encodings. */
   pub fn fetch_name (&mut self, window: Window) -> String {
     unsafe {
       let mut name_ptr: *mut c_char = null_mut();
-      if ::ffi::XFetchName(self.ptr, window as c_ulong, &mut name_ptr) == 0 {
+      if ::ffi::XFetchName(self.ptr, window.0 as c_ulong, &mut name_ptr) == 0 {
         if name_ptr != null_mut() {
           ::ffi::XFree(name_ptr as *mut c_void);
         }
@@ -183,18 +545,17 @@ impl Display {
       if name_ptr == null_mut() {
         return String::new();
       }
-      let name_byte_ptr = name_ptr as *const c_char as *const u8;
-      let name_len = ::libc::strlen(name_ptr as *const c_char) as usize;
-      let name_slice = ::std::slice::from_raw_buf(&name_byte_ptr, name_len);
-      let name_string = String::from_utf8_lossy(name_slice).into_owned();
+      let name_string = c_str_to_latin1(name_ptr);
       ::ffi::XFree(name_ptr as *mut c_void);
       return name_string;
     }
   }
 
-  pub fn fill_rectangle (&mut self, drawable: Drawable, gc: Gcid, x: i32, y: i32, width: i32, height: i32) {
+  pub fn fill_rectangle<D> (&mut self, drawable: D, gc: Gcid, x: i32, y: i32, width: i32, height: i32)
+    where D: Drawable
+  {
     unsafe {
-      ::ffi::XFillRectangle(self.ptr, drawable as c_ulong, gc as c_ulong, x as c_int, y as c_int,
+      ::ffi::XFillRectangle(self.ptr, drawable.drawable_id() as c_ulong, gc.0 as c_ulong, x as c_int, y as c_int,
           width as c_uint, height as c_uint);
     }
   }
@@ -207,17 +568,68 @@ impl Display {
 
   pub fn free_colormap (&mut self, colormap: Colormap) {
     unsafe {
-      ::ffi::XFreeColormap(self.ptr, colormap as c_ulong);
+      ::ffi::XFreeColormap(self.ptr, colormap.0 as c_ulong);
     }
   }
 
   pub fn free_gc (&mut self, gc: Gcid) {
     unsafe {
-      ::ffi::XFreeGC(self.ptr, gc as c_ulong);
+      ::ffi::XFreeGC(self.ptr, gc.0 as c_ulong);
+    }
+  }
+
+  pub fn free_pixmap (&mut self, pixmap: Pixmap) {
+    unsafe {
+      ::ffi::XFreePixmap(self.ptr, pixmap.0 as c_ulong);
+    }
+  }
+
+  pub fn get_class_hint (&mut self, window: Window) -> Option<ClassHint> {
+    unsafe {
+      let mut xhint = ::ffi::XClassHint {
+        res_name: null_mut(),
+        res_class: null_mut(),
+      };
+      if ::ffi::XGetClassHint(self.ptr, window.0 as c_ulong, &mut xhint) == 0 {
+        return None;
+      }
+      let hint = ClassHint {
+        instance: c_str_to_latin1(xhint.res_name),
+        class: c_str_to_latin1(xhint.res_class),
+      };
+      if xhint.res_name != null_mut() {
+        ::ffi::XFree(xhint.res_name as *mut c_void);
+      }
+      if xhint.res_class != null_mut() {
+        ::ffi::XFree(xhint.res_class as *mut c_void);
+      }
+      return Some(hint);
+    }
+  }
+
+  /** Returns the WM_COMMAND arguments, or `None` if the property is not set. */
+  pub fn get_command (&mut self, window: Window) -> Option<Vec<String>> {
+    unsafe {
+      let mut argv: *mut *mut c_char = null_mut();
+      let mut argc: c_int = 0;
+      if ::ffi::XGetCommand(self.ptr, window.0 as c_ulong, &mut argv, &mut argc) == 0 {
+        return None;
+      }
+      let mut arg_vec = Vec::with_capacity(argc as usize);
+      if argv != null_mut() {
+        let argv_const = argv as *const *mut c_char;
+        for arg in ::std::slice::from_raw_buf(&argv_const, argc as usize).iter() {
+          arg_vec.push(c_str_to_latin1(*arg));
+        }
+        ::ffi::XFreeStringList(argv);
+      }
+      return Some(arg_vec);
     }
   }
 
-  pub fn get_geometry (&mut self, drawable: Drawable) -> Option<Geometry> {
+  pub fn get_geometry<D> (&mut self, drawable: D) -> Option<Geometry>
+    where D: Drawable
+  {
     unsafe {
       let mut root = 0;
       let mut x = 0;
@@ -227,14 +639,14 @@ impl Display {
       let mut border_width = 0;
       let mut depth = 0;
 
-      if ::ffi::XGetGeometry(self.ptr, drawable as c_ulong, &mut root, &mut x, &mut y, &mut width, &mut height,
-          &mut border_width, &mut depth) == 0
+      if ::ffi::XGetGeometry(self.ptr, drawable.drawable_id() as c_ulong, &mut root, &mut x, &mut y, &mut width,
+          &mut height, &mut border_width, &mut depth) == 0
       {
         return None;
       }
 
       let geometry = Geometry {
-        root: root as Window,
+        root: Window(root as Xid),
         x: x as i32,
         y: y as i32,
         width: width as i32,
@@ -246,6 +658,100 @@ impl Display {
     }
   }
 
+  pub fn get_icon_name (&mut self, window: Window) -> Option<String> {
+    unsafe {
+      let mut name_ptr: *mut c_char = null_mut();
+      if ::ffi::XGetIconName(self.ptr, window.0 as c_ulong, &mut name_ptr) == 0 || name_ptr == null_mut() {
+        return None;
+      }
+      let name_string = c_str_to_latin1(name_ptr);
+      ::ffi::XFree(name_ptr as *mut c_void);
+      return Some(name_string);
+    }
+  }
+
+  /** Reads a window property, fetching it in chunks until the whole value has been read. Returns `None` if the
+   * property does not exist or does not match `kind`. If `delete` is set, the property is deleted once it has been
+   * read in full. */
+  pub fn get_property (&mut self, window: Window, property: Atom, kind: Option<Atom>, delete: bool)
+      -> Option<Property> {
+    unsafe {
+      let req_type = match kind {Some(atom) => atom.0 as c_ulong, None => ::ffi::AnyPropertyType};
+      let mut offset: c_long = 0;
+      let mut first_type: c_ulong = 0;
+      let mut first_format: c_int = 0;
+      let mut data: Option<PropertyData> = None;
+      loop {
+        let mut actual_type: c_ulong = 0;
+        let mut actual_format: c_int = 0;
+        let mut nitems: c_ulong = 0;
+        let mut bytes_after: c_ulong = 0;
+        let mut data_ptr: *mut c_uchar = null_mut();
+        let status = ::ffi::XGetWindowProperty(self.ptr, window.0 as c_ulong, property.0 as c_ulong, offset,
+            PROPERTY_CHUNK_LENGTH, if delete {1} else {0}, req_type, &mut actual_type, &mut actual_format,
+            &mut nitems, &mut bytes_after, &mut data_ptr);
+        if status != ::ffi::Success {
+          return None;
+        }
+        if actual_type == 0 || (kind.is_some() && actual_type != req_type) {
+          if data_ptr != null_mut() {
+            ::ffi::XFree(data_ptr as *mut c_void);
+          }
+          return None;
+        }
+
+        // the property changed between chunks, so start over
+        if offset != 0 && (actual_type != first_type || actual_format != first_format) {
+          if data_ptr != null_mut() {
+            ::ffi::XFree(data_ptr as *mut c_void);
+          }
+          offset = 0;
+          data = None;
+          continue;
+        }
+        first_type = actual_type;
+        first_format = actual_format;
+
+        data = append_property_chunk(data, actual_format, data_ptr, nitems as usize);
+        if data_ptr != null_mut() {
+          ::ffi::XFree(data_ptr as *mut c_void);
+        }
+        if data.is_none() {
+          error!("XGetWindowProperty returned invalid format: {}", actual_format);
+          return None;
+        }
+        if bytes_after == 0 {
+          return data.map(|data| Property {
+            kind: Atom(first_type as Xid),
+            data: data,
+          });
+        }
+        offset += PROPERTY_CHUNK_LENGTH;
+      }
+    }
+  }
+
+  /** Reads a text property in any of the ICCCM encodings and returns its elements as Unicode strings. */
+  pub fn get_text_property (&mut self, window: Window, property: Atom) -> Option<Vec<String>> {
+    unsafe {
+      let mut xtext: ::ffi::XTextProperty = zeroed();
+      if ::ffi::XGetTextProperty(self.ptr, window.0 as c_ulong, &mut xtext, property.0 as c_ulong) == 0 {
+        return None;
+      }
+      return self.decode_text_property(xtext);
+    }
+  }
+
+  pub fn get_transient_for_hint (&mut self, window: Window) -> Option<Window> {
+    unsafe {
+      let mut prop_window: c_ulong = 0;
+      if ::ffi::XGetTransientForHint(self.ptr, window.0 as c_ulong, &mut prop_window) == 0 {
+        return None;
+      }
+      return Some(Window(prop_window as Xid));
+    }
+  }
+
   pub fn get_visual_info (&mut self, template: VisualTemplate) -> Vec<VisualInfo> {
     unsafe {
       let mut info_vec = Vec::new();
@@ -269,10 +775,25 @@ impl Display {
     }
   }
 
+  /** Returns the window's geometry with `x` and `y` relative to the root window, measured to the outer corner of
+   * the border like `get_geometry`. */
+  pub fn get_window_absolute_geometry (&mut self, window: Window) -> Option<Geometry> {
+    let mut geometry = match self.get_geometry(window) {Some(g) => g, None => return None};
+    let border_width = geometry.border_width;
+    match self.translate_coordinates(window, geometry.root, -border_width, -border_width) {
+      Some((x, y, _)) => {
+        geometry.x = x;
+        geometry.y = y;
+        return Some(geometry);
+      },
+      None => return None,
+    }
+  }
+
   pub fn get_window_attributes (&mut self, window: Window) -> Option<WindowAttributes> {
     unsafe {
       let mut xattr: ::ffi::XWindowAttributes = zeroed();
-      if ::ffi::XGetWindowAttributes(self.ptr, window as c_ulong, &mut xattr) == 0 {
+      if ::ffi::XGetWindowAttributes(self.ptr, window.0 as c_ulong, &mut xattr) == 0 {
         return None;
       }
       if let Some(attr) = FromNative::from_native(xattr) {
@@ -284,68 +805,250 @@ impl Display {
     }
   }
 
-  pub fn intern_atom (&mut self, name: &str, only_if_exists: bool) -> Option<Atom> {
+  pub fn get_wm_client_machine (&mut self, window: Window) -> Option<String> {
+    let property = match self.intern_atom("WM_CLIENT_MACHINE", true) {Some(a) => a, None => return None};
+    match self.get_property(window, property, None, false) {
+      Some(Property { data: PropertyData::Byte(ref bytes), .. }) => Some(latin1_to_string(bytes)),
+      _ => None,
+    }
+  }
+
+  pub fn get_wm_hints (&mut self, window: Window) -> Option<WmHints> {
     unsafe {
-      let name_c_str = CString::from_slice(name.as_bytes());
-      let atom = ::ffi::XInternAtom(self.ptr, name_c_str.as_ptr(), if only_if_exists {1} else {0});
-      if atom == 0 {
+      let xhints_ptr = ::ffi::XGetWMHints(self.ptr, window.0 as c_ulong);
+      if xhints_ptr == null_mut() {
         return None;
-      } else {
-        return Some(atom as Atom);
       }
+      let hints = FromNative::from_native(*xhints_ptr);
+      ::ffi::XFree(xhints_ptr as *mut c_void);
+      return Some(hints);
     }
   }
 
-  pub fn map_window (&mut self, window: Window) {
+  /** Reads WM_NAME, decoding STRING, COMPOUND_TEXT and UTF8_STRING names. */
+  pub fn get_wm_name (&mut self, window: Window) -> Option<String> {
     unsafe {
-      ::ffi::XMapWindow(self.ptr, window as c_ulong);
+      let mut xtext: ::ffi::XTextProperty = zeroed();
+      if ::ffi::XGetWMName(self.ptr, window.0 as c_ulong, &mut xtext) == 0 {
+        return None;
+      }
+      return self.decode_text_property(xtext).map(|list| list.concat());
     }
   }
 
-  pub fn move_window (&mut self, window: Window, x: i32, y: i32) {
+  /** Reads WM_NORMAL_HINTS. The mask reports which fields are present in the client's property, while absent
+   * hints are `None` in the returned `SizeHints`. */
+  pub fn get_wm_normal_hints (&mut self, window: Window) -> Option<(SizeHints, SizeHintsMask)> {
     unsafe {
-      ::ffi::XMoveWindow(self.ptr, window as c_ulong, x as c_int, y as c_int);
+      let mut xhints: ::ffi::XSizeHints = zeroed();
+      let mut supplied: c_long = 0;
+      if ::ffi::XGetWMNormalHints(self.ptr, window.0 as c_ulong, &mut xhints, &mut supplied) == 0 {
+        return None;
+      }
+      return Some((FromNative::from_native(xhints), FromNative::from_native(xhints.flags & supplied)));
     }
   }
 
-  pub fn next_event (&mut self) -> Event {
+  /** Blocks until an event matching `predicate` is queued, then removes and returns it. If `predicate` panics, the
+   * panic is resumed after the event it panicked on has been put back at the head of the queue, so it moves ahead
+   * of any earlier events the predicate rejected. */
+  pub fn if_event<F> (&mut self, mut predicate: F) -> Result<Event, IoError>
+    where F: FnMut(&Event) -> bool
+  {
+    try!(self.check_connection());
     unsafe {
       let mut xevent: ::ffi::XEvent = zeroed();
-      loop {
-        ::ffi::XNextEvent(self.ptr, &mut xevent);
-        if let Some(event) = FromNative::from_native(xevent) {
-          return event;
-        }
+      let mut state = PredicateState {
+        predicate: &mut predicate,
+        panic: None,
+        match_on_panic: true,
+      };
+      let arg = &mut state as *mut PredicateState as ::ffi::XPointer;
+      ::ffi::XIfEvent(self.ptr, &mut xevent, event_predicate, arg);
+      if let Some(payload) = state.panic.take() {
+        // the event was only taken to end the wait, so leave it in the queue
+        ::ffi::XPutBackEvent(self.ptr, &mut xevent);
+        resume_unwind(payload);
       }
+      try!(self.check_connection());
+      return Ok(FromNative::from_native(xevent));
     }
   }
 
-  pub fn open (name: &str) -> Option<Display> {
+  pub fn image_byte_order (&mut self) -> ByteOrder {
     unsafe {
-      init();
-      let name_c_str = CString::from_slice(name.as_bytes());
-      let ptr = ::ffi::XOpenDisplay(name_c_str.as_ptr());
-      if ptr == null_mut() {
-        return None;
+      if let Some(order) = FromNative::from_native(::ffi::XImageByteOrder(self.ptr)) {
+        return order;
+      } else {
+        error!("XImageByteOrder returned invalid data");
+        return ByteOrder::LsbFirst;
       }
-      let display = Display {
-        ptr: ptr,
-      };
-      return Some(display);
     }
   }
 
-  pub fn open_default () -> Option<Display> {
+  pub fn intern_atom (&mut self, name: &str, only_if_exists: bool) -> Option<Atom> {
     unsafe {
-      init();
-      let ptr = ::ffi::XOpenDisplay(null());
-      if ptr == null_mut() {
-        return None;
+      let name_c_str = CString::from_slice(name.as_bytes());
+      let atom = ::ffi::XInternAtom(self.ptr, name_c_str.as_ptr(), if only_if_exists {1} else {0});
+      if atom == 0 {
+        return None;
+      } else {
+        return Some(Atom(atom as Xid));
+      }
+    }
+  }
+
+  pub fn list_pixmap_formats (&mut self) -> Vec<PixmapFormat> {
+    unsafe {
+      let mut format_vec = Vec::new();
+      let mut count = 0;
+      let xformats_ptr = ::ffi::XListPixmapFormats(self.ptr, &mut count);
+      if xformats_ptr == null_mut() {
+        return format_vec;
+      }
+      let xformats_const_ptr = xformats_ptr as *const ::ffi::XPixmapFormatValues;
+      let xformats_slice = ::std::slice::from_raw_buf(&xformats_const_ptr, count as usize);
+      for xformat in xformats_slice.iter() {
+        format_vec.push(FromNative::from_native(*xformat));
+      }
+      ::ffi::XFree(xformats_ptr as *mut c_void);
+      return format_vec;
+    }
+  }
+
+  pub fn list_properties (&mut self, window: Window) -> Vec<Atom> {
+    unsafe {
+      let mut atom_vec = Vec::new();
+      let mut count = 0;
+      let atoms_ptr = ::ffi::XListProperties(self.ptr, window.0 as c_ulong, &mut count);
+      if atoms_ptr == null_mut() {
+        return atom_vec;
       }
+      let atoms_const_ptr = atoms_ptr as *const c_ulong;
+      for atom in ::std::slice::from_raw_buf(&atoms_const_ptr, count as usize).iter() {
+        atom_vec.push(Atom(*atom as Xid));
+      }
+      ::ffi::XFree(atoms_ptr as *mut c_void);
+      return atom_vec;
+    }
+  }
+
+  pub fn lookup_keysym (&mut self, event: &KeyEvent, index: i32) -> Keysym {
+    unsafe {
+      let mut xkey: ::ffi::XKeyEvent = event.to_native();
+      xkey.display = self.ptr;
+      return ::ffi::XLookupKeysym(&mut xkey, index as c_int) as Keysym;
+    }
+  }
+
+  /** Translates a key event into a keysym and the text it produces, if any. */
+  pub fn lookup_string (&mut self, event: &KeyEvent) -> (Keysym, String) {
+    unsafe {
+      let mut xkey: ::ffi::XKeyEvent = event.to_native();
+      xkey.display = self.ptr;
+      let mut buf = [0u8; 32];
+      let buf_ptr = &mut buf[0] as *mut u8 as *mut c_char;
+      let mut keysym: c_ulong = 0;
+      let len = ::ffi::XLookupString(&mut xkey, buf_ptr, buf.len() as c_int, &mut keysym, null_mut());
+      let mut text = if len > 0 {latin1_to_string(&buf[..len as usize])} else {String::new()};
+
+      // XLookupString only knows Latin-1, so fall back on Unicode keysyms (0x01000000 + code point)
+      if text.is_empty() && keysym & 0xff00_0000 == 0x0100_0000 {
+        if let Some(c) = ::std::char::from_u32((keysym & 0x00ff_ffff) as u32) {
+          text.push(c);
+        }
+      }
+      return (keysym as Keysym, text);
+    }
+  }
+
+  pub fn lower_window (&mut self, window: Window) {
+    unsafe {
+      ::ffi::XLowerWindow(self.ptr, window.0 as c_ulong);
+    }
+  }
+
+  pub fn map_window (&mut self, window: Window) {
+    unsafe {
+      ::ffi::XMapWindow(self.ptr, window.0 as c_ulong);
+    }
+  }
+
+  /** Returns the maximum request length in 4-byte units, using the BIG-REQUESTS limit if the server supports it. */
+  pub fn max_request_size (&mut self) -> u32 {
+    unsafe {
+      let extended = ::ffi::XExtendedMaxRequestSize(self.ptr);
+      if extended != 0 {
+        return extended as u32;
+      }
+      return ::ffi::XMaxRequestSize(self.ptr) as u32;
+    }
+  }
+
+  pub fn motion_buffer_size (&mut self) -> u32 {
+    unsafe {
+      return ::ffi::XDisplayMotionBufferSize(self.ptr) as u32;
+    }
+  }
+
+  pub fn move_window (&mut self, window: Window, x: i32, y: i32) {
+    unsafe {
+      ::ffi::XMoveWindow(self.ptr, window.0 as c_ulong, x as c_int, y as c_int);
+    }
+  }
+
+  pub fn next_event (&mut self) -> Result<Event, IoError> {
+    try!(self.check_connection());
+    unsafe {
+      let mut xevent: ::ffi::XEvent = zeroed();
+      ::ffi::XNextEvent(self.ptr, &mut xevent);
+      try!(self.check_connection());
+      return Ok(FromNative::from_native(xevent));
+    }
+  }
+
+  pub fn open (name: &str) -> Result<Display, OpenError> {
+    if name.contains('\0') {
+      return Err(OpenError::InvalidName);
+    }
+    let address = match parse_display_name(name) {
+      Some(address) => address,
+      None => { return Err(OpenError::InvalidName); },
+    };
+    unsafe {
+      init();
+      let name_c_str = CString::from_slice(name.as_bytes());
+      let ptr = ::ffi::XOpenDisplay(name_c_str.as_ptr());
+      if ptr == null_mut() {
+        return Err(diagnose_open_failure(&address));
+      }
+      ::ffi::XSetIOErrorExitHandler(ptr, handle_io_error_exit, null_mut());
       let display = Display {
         ptr: ptr,
+        connection: Arc::new(Connection {
+          ptr: ptr,
+        }),
       };
-      return Some(display);
+      return Ok(display);
+    }
+  }
+
+  /** Opens the display named by the `DISPLAY` environment variable. */
+  pub fn open_default () -> Result<Display, OpenError> {
+    match var("DISPLAY") {
+      Ok(ref name) if !name.is_empty() => Display::open(name),
+      _ => Err(OpenError::DisplayUnset),
+    }
+  }
+
+  /** Returns the next event without removing it from the queue. Blocks if the queue is empty. */
+  pub fn peek_event (&mut self) -> Result<Event, IoError> {
+    try!(self.check_connection());
+    unsafe {
+      let mut xevent: ::ffi::XEvent = zeroed();
+      ::ffi::XPeekEvent(self.ptr, &mut xevent);
+      try!(self.check_connection());
+      return Ok(FromNative::from_native(xevent));
     }
   }
 
@@ -355,18 +1058,126 @@ impl Display {
     }
   }
 
+  /** Returns an iterator over the events that can be read without blocking. If the connection is lost, the error
+   * is yielded once and the iterator ends. */
+  pub fn pending_events (&mut self) -> PendingEvents {
+    PendingEvents {
+      display: self,
+      done: false,
+    }
+  }
+
+  /** Removes and returns the next event if one is available, without blocking. */
+  pub fn poll_event (&mut self) -> Result<Option<Event>, IoError> {
+    try!(self.check_connection());
+    if self.pending() == 0 {
+      // XPending reads from the connection, so this is where a lost connection is noticed
+      return self.check_connection().map(|_| None);
+    }
+    return self.next_event().map(Some);
+  }
+
+  pub fn protocol_revision (&mut self) -> i32 {
+    unsafe {
+      return ::ffi::XProtocolRevision(self.ptr) as i32;
+    }
+  }
+
+  pub fn protocol_version (&mut self) -> i32 {
+    unsafe {
+      return ::ffi::XProtocolVersion(self.ptr) as i32;
+    }
+  }
+
+  pub fn put_back_event (&mut self, event: Event) {
+    unsafe {
+      let mut xevent = event.to_native();
+      ::ffi::XPutBackEvent(self.ptr, &mut xevent);
+    }
+  }
+
+  pub fn query_tree (&mut self, window: Window) -> Option<WindowTree> {
+    unsafe {
+      let mut root: c_ulong = 0;
+      let mut parent: c_ulong = 0;
+      let mut children_ptr: *mut c_ulong = null_mut();
+      let mut nchildren: c_uint = 0;
+      if ::ffi::XQueryTree(self.ptr, window.0 as c_ulong, &mut root, &mut parent, &mut children_ptr, &mut nchildren)
+          == 0
+      {
+        return None;
+      }
+      let mut children = Vec::with_capacity(nchildren as usize);
+      if children_ptr != null_mut() {
+        let children_const_ptr = children_ptr as *const c_ulong;
+        for child in ::std::slice::from_raw_buf(&children_const_ptr, nchildren as usize).iter() {
+          children.push(Window(*child as Xid));
+        }
+        ::ffi::XFree(children_ptr as *mut c_void);
+      }
+      let tree = WindowTree {
+        root: Window(root as Xid),
+        parent: if parent == 0 {None} else {Some(Window(parent as Xid))},
+        children: children,
+      };
+      return Some(tree);
+    }
+  }
+
+  pub fn raise_window (&mut self, window: Window) {
+    unsafe {
+      ::ffi::XRaiseWindow(self.ptr, window.0 as c_ulong);
+    }
+  }
+
   pub fn resize_window (&mut self, window: Window, width: i32, height: i32) {
     unsafe {
-      ::ffi::XResizeWindow(self.ptr, window as c_ulong, width as c_uint, height as c_uint);
+      ::ffi::XResizeWindow(self.ptr, window.0 as c_ulong, width as c_uint, height as c_uint);
+    }
+  }
+
+  /** Restacks sibling windows so each one is directly below the one before it. The first window keeps its
+   * position relative to windows not in the list. */
+  pub fn restack_windows (&mut self, windows: &[Window]) {
+    unsafe {
+      if windows.is_empty() {
+        return;
+      }
+      let mut window_vec: Vec<c_ulong> = windows.iter().map(|window| window.0 as c_ulong).collect();
+      ::ffi::XRestackWindows(self.ptr, &mut window_vec[0], window_vec.len() as c_int);
     }
   }
 
   pub fn root_window (&mut self, screen_num: i32) -> Window {
     unsafe {
       if screen_num < 0 || screen_num >= self.screen_count() {
-        return 0;
+        return Window(0);
+      }
+      return Window(::ffi::XRootWindow(self.ptr, screen_num as c_int) as Xid);
+    }
+  }
+
+  pub fn rotate_properties (&mut self, window: Window, properties: &[Atom], positions: i32) {
+    unsafe {
+      if properties.is_empty() {
+        return;
+      }
+      let mut atom_vec: Vec<c_ulong> = properties.iter().map(|atom| atom.0 as c_ulong).collect();
+      ::ffi::XRotateWindowProperties(self.ptr, window.0 as c_ulong, &mut atom_vec[0], atom_vec.len() as c_int,
+          positions as c_int);
+    }
+  }
+
+  pub fn screen (&self, screen_num: i32) -> Option<Screen> {
+    unsafe {
+      if screen_num < 0 || screen_num >= ::ffi::XScreenCount(self.ptr) {
+        return None;
+      }
+      let ptr = ::ffi::XScreenOfDisplay(self.ptr, screen_num as c_int);
+      if ptr == null() {
+        return None;
       }
-      return ::ffi::XRootWindow(self.ptr, screen_num as c_int) as Window;
+      return Some(FromNative::from_native(ptr));
     }
   }
 
@@ -376,6 +1187,12 @@ impl Display {
     }
   }
 
+  pub fn select_input (&mut self, window: Window, event_mask: EventMask) {
+    unsafe {
+      ::ffi::XSelectInput(self.ptr, window.0 as c_ulong, event_mask.to_native());
+    }
+  }
+
   pub fn send_event (&mut self, propagate: bool, event_mask: EventMask, event: Event) -> bool {
     unsafe {
       let xevent = event.to_native();
@@ -384,6 +1201,52 @@ impl Display {
     }
   }
 
+  /** Like `send_event`, but delivers the event to `destination` instead of the window named in the event. */
+  pub fn send_event_to (&mut self, destination: Window, propagate: bool, event_mask: EventMask, event: Event) -> bool {
+    unsafe {
+      let xevent = event.to_native();
+      return ::ffi::XSendEvent(self.ptr, destination.0 as c_ulong, if propagate {1} else {0}, event_mask.to_native(),
+          &xevent) != 0;
+    }
+  }
+
+  pub fn server_vendor (&mut self) -> String {
+    unsafe {
+      let vendor_ptr = ::ffi::XServerVendor(self.ptr);
+      if vendor_ptr == null_mut() {
+        return String::new();
+      }
+      let vendor_byte_ptr = vendor_ptr as *const c_char as *const u8;
+      let vendor_len = ::libc::strlen(vendor_ptr as *const c_char) as usize;
+      let vendor_slice = ::std::slice::from_raw_buf(&vendor_byte_ptr, vendor_len);
+      return latin1_to_string(vendor_slice);
+    }
+  }
+
+  /** Calls `function` with the serial number of each request after it is sent. */
+  pub fn set_after_function<F> (&mut self, function: F)
+    where F: FnMut(u32) + Send + 'static
+  {
+    self.update_after_state(|state| state.function = Some(Box::new(function)));
+  }
+
+  /** Returns false without setting anything if either name contains a NUL character. */
+  pub fn set_class_hint (&mut self, window: Window, hint: &ClassHint) -> bool {
+    if hint.instance.contains('\0') || hint.class.contains('\0') {
+      return false;
+    }
+    unsafe {
+      let instance_c_str = CString::from_vec(string_to_latin1(&hint.instance));
+      let class_c_str = CString::from_vec(string_to_latin1(&hint.class));
+      let xhint = ::ffi::XClassHint {
+        res_name: instance_c_str.as_ptr() as *mut c_char,
+        res_class: class_c_str.as_ptr() as *mut c_char,
+      };
+      ::ffi::XSetClassHint(self.ptr, window.0 as c_ulong, &xhint);
+      return true;
+    }
+  }
+
   pub fn set_clip_rectangles (&mut self, gc: Gcid, x_origin: i32, y_origin: i32, rects: &[Rectangle],
       ordering: ClipOrdering)
   {
@@ -392,47 +1255,267 @@ impl Display {
       for rect in rects.iter() {
         xrects.push(rect.to_native());
       }
-      ::ffi::XSetClipRectangles(self.ptr, gc as c_ulong, x_origin as c_int, y_origin as c_int, &xrects[0],
+      ::ffi::XSetClipRectangles(self.ptr, gc.0 as c_ulong, x_origin as c_int, y_origin as c_int, &xrects[0],
           xrects.len() as c_int, ordering.to_native());
     }
   }
 
+  /** Returns false without setting anything if an argument contains a NUL character. */
+  pub fn set_command (&mut self, window: Window, args: &[&str]) -> bool {
+    if args.iter().any(|arg| arg.contains('\0')) {
+      return false;
+    }
+    unsafe {
+      let arg_c_strs: Vec<CString> = args.iter().map(|arg| CString::from_vec(string_to_latin1(*arg))).collect();
+      let mut argv: Vec<*mut c_char> = arg_c_strs.iter().map(|arg| arg.as_ptr() as *mut c_char).collect();
+      ::ffi::XSetCommand(self.ptr, window.0 as c_ulong, argv.as_mut_ptr(), argv.len() as c_int);
+      return true;
+    }
+  }
+
   pub fn set_foreground (&mut self, gc: Gcid, pixel: u32) {
     unsafe {
-      ::ffi::XSetForeground(self.ptr, gc as c_ulong, pixel as c_ulong);
+      ::ffi::XSetForeground(self.ptr, gc.0 as c_ulong, pixel as c_ulong);
+    }
+  }
+
+  /** Returns false without setting anything if `name` contains a NUL character. */
+  pub fn set_icon_name (&mut self, window: Window, name: &str) -> bool {
+    if name.contains('\0') {
+      return false;
+    }
+    unsafe {
+      let name_c_str = CString::from_vec(string_to_latin1(name));
+      ::ffi::XSetIconName(self.ptr, window.0 as c_ulong, name_c_str.as_ptr());
+      return true;
+    }
+  }
+
+  /** Logs every request with its serial number at debug level. Combined with `set_synchronous`, protocol errors
+   * are logged right after the request that caused them. */
+  pub fn set_request_tracing (&mut self, trace: bool) {
+    self.update_after_state(|state| state.trace = trace);
+  }
+
+  /** Makes every request wait for the server's reply, so that errors are reported as soon as they occur. */
+  pub fn set_synchronous (&mut self, synchronous: bool) {
+    self.update_after_state(|state| state.synchronous = synchronous);
+  }
+
+  pub fn set_text_property (&mut self, window: Window, property: Atom, list: &[&str], encoding: TextEncoding)
+      -> bool {
+    unsafe {
+      if let Some(xtext) = self.encode_text_property(list, encoding) {
+        ::ffi::XSetTextProperty(self.ptr, window.0 as c_ulong, &xtext, property.0 as c_ulong);
+        ::ffi::XFree(xtext.value as *mut c_void);
+        return true;
+      }
+      return false;
+    }
+  }
+
+  pub fn set_transient_for_hint (&mut self, window: Window, transient_for: Window) {
+    unsafe {
+      ::ffi::XSetTransientForHint(self.ptr, window.0 as c_ulong, transient_for.0 as c_ulong);
+    }
+  }
+
+  pub fn set_window_border_width (&mut self, window: Window, width: i32) {
+    unsafe {
+      ::ffi::XSetWindowBorderWidth(self.ptr, window.0 as c_ulong, width as c_uint);
+    }
+  }
+
+  /** Returns false without setting anything if `machine` contains a NUL character. */
+  pub fn set_wm_client_machine (&mut self, window: Window, machine: &str) -> bool {
+    if machine.contains('\0') {
+      return false;
+    }
+    let property = self.intern_atom("WM_CLIENT_MACHINE", false).unwrap_or(Atom(0));
+    let kind = self.intern_atom("STRING", false).unwrap_or(Atom(0));
+    let data = PropertyData::Byte(string_to_latin1(machine));
+    self.change_property(window, property, kind, PropertyMode::Replace, &data);
+    return true;
+  }
+
+  pub fn set_wm_hints (&mut self, window: Window, hints: WmHints) {
+    unsafe {
+      let xhints = hints.to_native();
+      ::ffi::XSetWMHints(self.ptr, window.0 as c_ulong, &xhints);
+    }
+  }
+
+  /** Sets WM_NAME, stored as STRING if the name fits in Latin-1 and as COMPOUND_TEXT otherwise. */
+  pub fn set_wm_name (&mut self, window: Window, name: &str) -> bool {
+    unsafe {
+      if let Some(xtext) = self.encode_text_property(&[name], TextEncoding::StdIccText) {
+        ::ffi::XSetWMName(self.ptr, window.0 as c_ulong, &xtext);
+        ::ffi::XFree(xtext.value as *mut c_void);
+        return true;
+      }
+      return false;
     }
   }
 
   pub fn set_wm_normal_hints (&mut self, window: Window, hints: SizeHints) {
     unsafe {
       let xhints = hints.to_native();
-      ::ffi::XSetWMNormalHints(self.ptr, window as c_ulong, &xhints);
+      ::ffi::XSetWMNormalHints(self.ptr, window.0 as c_ulong, &xhints);
+    }
+  }
+
+  /** Sets the standard client properties in one call, like XSetWMProperties. Properties passed as `None` or empty
+   * are left untouched. WM_CLIENT_MACHINE is set to the local host name. Returns false if a string contains a NUL
+   * character; that property is skipped, the others are still set. */
+  pub fn set_wm_properties (&mut self, window: Window, name: Option<&str>, icon_name: Option<&str>, args: &[&str],
+      normal_hints: Option<SizeHints>, wm_hints: Option<WmHints>, class_hint: Option<&ClassHint>) -> bool {
+    let mut ok = true;
+    if let Some(name) = name {
+      ok &= self.store_name(window, name);
+    }
+    if let Some(icon_name) = icon_name {
+      ok &= self.set_icon_name(window, icon_name);
+    }
+    if !args.is_empty() {
+      ok &= self.set_command(window, args);
+    }
+    if let Some(host_name) = host_name() {
+      self.set_wm_client_machine(window, &host_name);
+    }
+    if let Some(normal_hints) = normal_hints {
+      self.set_wm_normal_hints(window, normal_hints);
+    }
+    if let Some(wm_hints) = wm_hints {
+      self.set_wm_hints(window, wm_hints);
     }
+    if let Some(class_hint) = class_hint {
+      ok &= self.set_class_hint(window, class_hint);
+    }
+    return ok;
   }
 
   pub fn set_wm_protocols (&mut self, window: Window, protocols: &[Atom]) {
     unsafe {
       let mut protocol_vec: Vec<c_ulong> = Vec::with_capacity(protocols.len());
       for protocol in protocols.iter() {
-        protocol_vec.push(*protocol as c_ulong);
+        protocol_vec.push(protocol.0 as c_ulong);
       }
-      if ::ffi::XSetWMProtocols(self.ptr, window as c_ulong, &protocol_vec[0], protocols.len() as c_int) == 0 {
+      if ::ffi::XSetWMProtocols(self.ptr, window.0 as c_ulong, &protocol_vec[0], protocols.len() as c_int) == 0 {
         error!("XSetWMProtocols failed");
       }
     }
   }
 
-  pub fn store_name (&mut self, window: Window, name: &str) {
+  /** Sets WM_NAME as Latin-1 text; other characters are replaced with '?'. Use `set_wm_name` for full Unicode.
+   * Returns false without setting anything if `name` contains a NUL character. */
+  pub fn store_name (&mut self, window: Window, name: &str) -> bool {
+    if name.contains('\0') {
+      return false;
+    }
     unsafe {
-      let name_c_str = CString::from_slice(name.as_bytes());
-      ::ffi::XStoreName(self.ptr, window as c_ulong, name_c_str.as_ptr());
+      let name_c_str = CString::from_vec(string_to_latin1(name));
+      ::ffi::XStoreName(self.ptr, window.0 as c_ulong, name_c_str.as_ptr());
+      return true;
+    }
+  }
+
+  /** Flushes the output buffer and waits until the server has processed every request. If `discard` is set,
+   * the event queue is emptied afterwards. */
+  pub fn sync (&mut self, discard: bool) {
+    unsafe {
+      ::ffi::XSync(self.ptr, if discard {1} else {0});
+    }
+  }
+
+  /** Converts coordinates relative to `src` into coordinates relative to `dst`. Also returns the child of `dst`
+   * containing the point, if any. Returns `None` if the windows are on different screens. */
+  pub fn translate_coordinates (&mut self, src: Window, dst: Window, x: i32, y: i32)
+      -> Option<(i32, i32, Option<Window>)> {
+    unsafe {
+      let mut dst_x: c_int = 0;
+      let mut dst_y: c_int = 0;
+      let mut child: c_ulong = 0;
+      if ::ffi::XTranslateCoordinates(self.ptr, src.0 as c_ulong, dst.0 as c_ulong, x as c_int, y as c_int,
+          &mut dst_x, &mut dst_y, &mut child) == 0
+      {
+        return None;
+      }
+      let child = if child == 0 {None} else {Some(Window(child as Xid))};
+      return Some((dst_x as i32, dst_y as i32, child));
+    }
+  }
+
+  /** Runs `f`, then waits for the server to process every request it made. Returns the first protocol error
+   * caused by those requests, if any. Errors trapped this way are not logged. */
+  pub fn trap_errors<T, F> (&mut self, f: F) -> Result<T, XError>
+    where F: FnOnce(&mut Display) -> T
+  {
+    unsafe {
+      // the guard removes the trap even if `f` panics, so later errors aren't swallowed by it
+      let mut guard = ErrorTrapGuard::push(self.ptr);
+      let value = f(self);
+      ::ffi::XSync(self.ptr, 0);
+      if let Some(error) = guard.remove().and_then(|trap| trap.error) {
+        return Err(error);
+      }
+      return Ok(value);
     }
   }
 
   pub fn unmap_window (&mut self, window: Window) {
     unsafe {
-      ::ffi::XUnmapWindow(self.ptr, window as c_ulong);
+      ::ffi::XUnmapWindow(self.ptr, window.0 as c_ulong);
+    }
+  }
+
+  pub fn vendor_release (&mut self) -> i32 {
+    unsafe {
+      return ::ffi::XVendorRelease(self.ptr) as i32;
+    }
+  }
+
+  /** Waits up to `timeout` for an event. Returns `None` if none arrived in time, or an error as soon as the
+   * connection is lost. */
+  pub fn wait_event (&mut self, timeout: Duration) -> Result<Option<Event>, IoError> {
+    let deadline = Instant::now() + timeout;
+    loop {
+      match self.poll_event() {
+        Ok(Some(event)) => return Ok(Some(event)),
+        Ok(None) => {},
+        Err(error) => return Err(error),
+      }
+      let now = Instant::now();
+      if now >= deadline {
+        return Ok(None);
+      }
+
+      // round up so we don't spin on sub-millisecond remainders
+      let remaining = deadline - now;
+      let millis = remaining.as_secs() * 1000 + (remaining.subsec_nanos() as u64 + 999_999) / 1_000_000;
+      let mut fd = pollfd {
+        fd: self.connection_number() as c_int,
+        events: POLLIN,
+        revents: 0,
+      };
+      unsafe {
+        poll(&mut fd, 1, if millis > c_int::max_value() as u64 {c_int::max_value()} else {millis as c_int});
+      }
+    }
+  }
+
+  /** Selects PropertyNotify events on `window`, keeping any events already selected by this client, and returns a
+   * filter for changes to `atoms`. Returns `None` without selecting anything if the window's current event mask
+   * can't be read, e.g. because the window doesn't exist. */
+  pub fn watch_properties (&mut self, window: Window, atoms: &[Atom]) -> Option<PropertyWatch> {
+    let mut event_mask = match self.get_window_attributes(window) {
+      Some(attr) => attr.your_event_mask,
+      None => return None,
+    };
+    if !event_mask.property_change {
+      event_mask.property_change = true;
+      self.select_input(window, event_mask);
     }
+    return Some(PropertyWatch::new(window, atoms));
   }
 
   pub fn white_pixel (&mut self, screen_num: i32) -> u32 {
@@ -445,45 +1528,753 @@ impl Display {
   }
 }
 
-impl Drop for Display {
+impl Display {
+  // Frees the property value.
+  unsafe fn decode_text_property (&mut self, xtext: ::ffi::XTextProperty) -> Option<Vec<String>> {
+    if xtext.value == null_mut() {
+      return Some(Vec::new());
+    }
+    let mut list: *mut *mut c_char = null_mut();
+    let mut count: c_int = 0;
+    let status = ::ffi::Xutf8TextPropertyToTextList(self.ptr, &xtext, &mut list, &mut count);
+    ::ffi::XFree(xtext.value as *mut c_void);
+    // positive values count unconvertible characters, which are replaced but don't fail the conversion
+    if status < ::ffi::Success {
+      error!("Xutf8TextPropertyToTextList failed: {}", status);
+      return None;
+    }
+    let mut string_vec = Vec::with_capacity(count as usize);
+    if list != null_mut() {
+      let list_const = list as *const *mut c_char;
+      for item in ::std::slice::from_raw_buf(&list_const, count as usize).iter() {
+        let byte_ptr = *item as *const u8;
+        let len = ::libc::strlen(*item as *const c_char) as usize;
+        string_vec.push(String::from_utf8_lossy(::std::slice::from_raw_buf(&byte_ptr, len)).into_owned());
+      }
+      ::ffi::XFreeStringList(list);
+    }
+    return Some(string_vec);
+  }
+
+  // The returned property value must be freed with XFree. Items can't contain NUL, which separates them.
+  unsafe fn encode_text_property (&mut self, list: &[&str], encoding: TextEncoding)
+      -> Option<::ffi::XTextProperty> {
+    if list.iter().any(|item| item.contains('\0')) {
+      return None;
+    }
+    let c_strs: Vec<CString> = list.iter().map(|item| CString::from_slice(item.as_bytes())).collect();
+    let mut ptrs: Vec<*mut c_char> = c_strs.iter().map(|c_str| c_str.as_ptr() as *mut c_char).collect();
+    let mut xtext: ::ffi::XTextProperty = zeroed();
+    let status = ::ffi::Xutf8TextListToTextProperty(self.ptr, ptrs.as_mut_ptr(), ptrs.len() as c_int,
+        encoding.to_native(), &mut xtext);
+    if status < ::ffi::Success {
+      error!("Xutf8TextListToTextProperty failed: {}", status);
+      return None;
+    }
+    return Some(xtext);
+  }
+
+  // XSynchronize and XSetAfterFunction replace each other's callback, so synchronous mode is handled by our own
+  // after function whenever a hook or tracing is active.
+  fn update_after_state<F> (&mut self, f: F)
+    where F: FnOnce(&mut AfterState)
+  {
+    unsafe {
+      let mut states = lock(&AFTER_STATES);
+      let index = match states.iter().position(|s| s.display == self.ptr as usize) {
+        Some(index) => index,
+        None => {
+          states.push(AfterState {
+            display: self.ptr as usize,
+            synchronous: false,
+            trace: false,
+            function: None,
+            running: false,
+          });
+          states.len() - 1
+        },
+      };
+      f(&mut states[index]);
+
+      let (synchronous, custom) = {
+        let state = &states[index];
+        (state.synchronous, state.trace || state.function.is_some())
+      };
+      if custom {
+        ::ffi::XSetAfterFunction(self.ptr, Some(after_request));
+      } else {
+        if !synchronous {
+          states.remove(index);
+        }
+        ::ffi::XSetAfterFunction(self.ptr, None);
+        ::ffi::XSynchronize(self.ptr, if synchronous {1} else {0});
+      }
+    }
+  }
+}
+
+
+//
+// DisplayLock
+//
+
+
+/** Exclusive access to a `SharedDisplay`. Also holds the Xlib display lock, so other code using the same
+ * connection directly through Xlib is kept out as well. */
+pub struct DisplayLock<'a> {
+  guard: MutexGuard<'a, Display>,
+}
+
+impl<'a> Deref for DisplayLock<'a> {
+  type Target = Display;
+
+  fn deref (&self) -> &Display {
+    &*self.guard
+  }
+}
+
+impl<'a> DerefMut for DisplayLock<'a> {
+  fn deref_mut (&mut self) -> &mut Display {
+    &mut *self.guard
+  }
+}
+
+impl<'a> Drop for DisplayLock<'a> {
   fn drop (&mut self) {
     unsafe {
-      ::ffi::XCloseDisplay(self.ptr);
+      ::ffi::XUnlockDisplay(self.guard.ptr);
     }
   }
 }
 
 
+//
+// Events
+//
+
+
+pub struct Events<'a> {
+  display: &'a mut Display,
+}
+
+impl<'a> Iterator for Events<'a> {
+  type Item = Event;
+
+  fn next (&mut self) -> Option<Event> {
+    self.display.next_event().ok()
+  }
+}
+
+
+//
+// PendingEvents
+//
+
+
+pub struct PendingEvents<'a> {
+  display: &'a mut Display,
+  done: bool,
+}
+
+impl<'a> Iterator for PendingEvents<'a> {
+  type Item = Result<Event, IoError>;
+
+  fn next (&mut self) -> Option<Result<Event, IoError>> {
+    if self.done {
+      return None;
+    }
+    match self.display.poll_event() {
+      Ok(Some(event)) => Some(Ok(event)),
+      Ok(None) => None,
+      Err(error) => {
+        self.done = true;
+        Some(Err(error))
+      },
+    }
+  }
+}
+
+
+//
+// SharedDisplay
+//
+
+
+/** A handle to a `Display` that can be cloned and sent between threads. Each thread locks the display while using
+ * it, so blocking calls like `next_event` hold up the other threads; prefer `wait_event` or `poll_event`. */
+#[derive(Clone)]
+pub struct SharedDisplay {
+  display: Arc<Mutex<Display>>,
+}
+
+// Xlib's own locking is enabled by init_threads, which SharedDisplay::new requires.
+unsafe impl Send for SharedDisplay {}
+unsafe impl Sync for SharedDisplay {}
+
+impl SharedDisplay {
+  /** Fails if `init_threads` wasn't called before the display was opened. */
+  pub fn new (display: Display) -> Option<SharedDisplay> {
+    unsafe {
+      if !_threads_init.load(Ordering::SeqCst) {
+        return None;
+      }
+    }
+    let shared = SharedDisplay {
+      display: Arc::new(Mutex::new(display)),
+    };
+    return Some(shared);
+  }
+
+  pub fn lock (&self) -> DisplayLock {
+    let guard = lock(&*self.display);
+    unsafe {
+      ::ffi::XLockDisplay(guard.ptr);
+    }
+    DisplayLock {
+      guard: guard,
+    }
+  }
+}
+
+
+//
+// event predicates
+//
+
+
+// A panicking predicate can't unwind through Xlib, so the panic is held here and resumed once Xlib returns.
+struct PredicateState<'a> {
+  predicate: &'a mut FnMut(&Event) -> bool,
+  panic: Option<Box<Any + Send>>,
+  // XIfEvent only returns on a match, so after a panic it has to be given one
+  match_on_panic: bool,
+}
+
+unsafe extern "C"
+fn event_predicate (_: *mut ::ffi::Display, event: *mut ::ffi::XEvent, arg: ::ffi::XPointer) -> ::ffi::Bool {
+  let state = &mut *(arg as *mut PredicateState);
+  if state.panic.is_none() {
+    let event = FromNative::from_native(*event);
+    let predicate = &mut state.predicate;
+    match catch_unwind(AssertUnwindSafe(|| predicate(&event))) {
+      Ok(matched) => return if matched {1} else {0},
+      Err(payload) => state.panic = Some(payload),
+    }
+  }
+  return if state.match_on_panic {1} else {0};
+}
+
+
+//
+// after functions
+//
+
+
+struct AfterState {
+  display: usize,
+  synchronous: bool,
+  trace: bool,
+  function: Option<Box<FnMut(u32) + Send>>,
+  running: bool,
+}
+
+static AFTER_STATES: Mutex<Vec<AfterState>> = Mutex::new(Vec::new());
+
+unsafe extern "C"
+fn after_request (display: *mut ::ffi::Display) -> c_int {
+  let serial = (::ffi::XNextRequest(display) - 1) as u32;
+
+  // the lock is released while calling out, since the function and XSync both make requests of their own
+  let (synchronous, mut function) = {
+    let mut states = lock(&AFTER_STATES);
+    let state = match states.iter_mut().find(|s| s.display == display as usize) {
+      Some(state) => state,
+      None => { return 0; },
+    };
+    if state.running {
+      return 0;
+    }
+    state.running = true;
+    if state.trace {
+      debug!("Xlib: Request serial {}", serial);
+    }
+    (state.synchronous, state.function.take())
+  };
+
+  if let Some(ref mut function) = function {
+    if catch_unwind(AssertUnwindSafe(|| function(serial))).is_err() {
+      error!("Xlib: after function panicked");
+    }
+  }
+  if synchronous {
+    ::ffi::XSync(display, 0);
+  }
+
+  let mut states = lock(&AFTER_STATES);
+  if let Some(state) = states.iter_mut().find(|s| s.display == display as usize) {
+    state.running = false;
+    if state.function.is_none() {
+      state.function = function;
+    }
+  }
+  return 0;
+}
+
+
+//
+// connection failures
+//
+
+
+#[derive(Debug, Eq, PartialEq)]
+enum DisplayAddress {
+  Unix(u32),
+  Tcp(String, u16),
+  Other,
+}
+
+// Display names have the form [protocol/][host]:display[.screen].
+fn parse_display_name (name: &str) -> Option<DisplayAddress> {
+  let colon = match name.rfind(':') {
+    Some(colon) => colon,
+    None => { return None; },
+  };
+  let (host_part, number_part) = (&name[..colon], &name[colon + 1..]);
+  let display_part = match number_part.find('.') {
+    Some(dot) => {
+      let screen_part = &number_part[dot + 1..];
+      if screen_part.is_empty() || !screen_part.chars().all(|c| c.is_digit(10)) {
+        return None;
+      }
+      &number_part[..dot]
+    },
+    None => number_part,
+  };
+  let number: u32 = match display_part.parse() {
+    Ok(number) => number,
+    Err(_) => { return None; },
+  };
+
+  // launchd socket paths and DECnet names aren't probed
+  if host_part.starts_with('/') || host_part.ends_with(':') {
+    return Some(DisplayAddress::Other);
+  }
+  let (protocol, host) = match host_part.find('/') {
+    Some(slash) => (&host_part[..slash], &host_part[slash + 1..]),
+    None => ("", host_part),
+  };
+  match protocol {
+    "" | "unix" | "local" if host.is_empty() || host == "unix" => Some(DisplayAddress::Unix(number)),
+    "" | "tcp" | "inet" | "inet6" => {
+      // display numbers beyond the last TCP port can't name a server
+      match 6000u32.checked_add(number) {
+        Some(port) if port <= ::std::u16::MAX as u32 => Some(DisplayAddress::Tcp(host.to_string(), port as u16)),
+        _ => None,
+      }
+    },
+    _ => Some(DisplayAddress::Other),
+  }
+}
+
+// Xlib doesn't say why XOpenDisplay failed, so connect to the server's socket ourselves. If that works, the server
+// must have turned the client away.
+fn diagnose_open_failure (address: &DisplayAddress) -> OpenError {
+  let result = match *address {
+    DisplayAddress::Unix(number) => UnixStream::connect(format!("/tmp/.X11-unix/X{}", number)).map(|_| ()),
+    DisplayAddress::Tcp(ref host, port) => {
+      let host = match host.trim_left_matches('[').trim_right_matches(']') {
+        "" => "localhost",
+        host => host,
+      };
+      let addrs: Vec<SocketAddr> = match (host, port).to_socket_addrs() {
+        Ok(addrs) => addrs.collect(),
+        Err(_) => { return OpenError::HostNotFound; },
+      };
+      if addrs.is_empty() {
+        return OpenError::HostNotFound;
+      }
+      TcpStream::connect(&addrs[..]).map(|_| ())
+    },
+    // launchd socket paths and DECnet names aren't probed
+    DisplayAddress::Other => { return OpenError::Unknown; },
+  };
+  match result {
+    Ok(()) => OpenError::AuthorizationRejected,
+    Err(ref e) if e.kind() == ErrorKind::ConnectionRefused || e.kind() == ErrorKind::NotFound => {
+      OpenError::ConnectionRefused
+    },
+    Err(e) => {
+      match e.raw_os_error() {
+        Some(errno) => OpenError::Io(errno),
+        None => OpenError::Unknown,
+      }
+    },
+  }
+}
+
+
+//
+// error handlers
+//
+
+
+// Each handler has its own lock, so it can be called without holding these; a handler may replace itself.
+static ERROR_HANDLER: Mutex<Option<Arc<Mutex<Box<FnMut(&XError) + Send>>>>> = Mutex::new(None);
+static IO_ERROR_HANDLER: Mutex<Option<Arc<Mutex<Box<FnMut(&IoError) + Send>>>>> = Mutex::new(None);
+
+/** Handles protocol errors that aren't caught by `Display::trap_errors`. By default they are logged. */
+pub fn set_error_handler<F> (handler: F)
+  where F: FnMut(&XError) + Send + 'static
+{
+  *lock(&ERROR_HANDLER) = Some(Arc::new(Mutex::new(Box::new(handler))));
+}
+
+/** Called when a connection is lost. Afterwards, blocking `Display` calls return the error. */
+pub fn set_io_error_handler<F> (handler: F)
+  where F: FnMut(&IoError) + Send + 'static
+{
+  *lock(&IO_ERROR_HANDLER) = Some(Arc::new(Mutex::new(Box::new(handler))));
+}
+
+pub fn reset_error_handler () {
+  *lock(&ERROR_HANDLER) = None;
+}
+
+pub fn reset_io_error_handler () {
+  *lock(&IO_ERROR_HANDLER) = None;
+}
+
+
+//
+// error traps
+//
+
+
+struct ErrorTrap {
+  display: usize,
+  first_serial: c_ulong,
+  error: Option<XError>,
+}
+
+struct ErrorTrapGuard {
+  display: usize,
+  first_serial: c_ulong,
+  removed: bool,
+}
+
+impl ErrorTrapGuard {
+  unsafe fn push (display: *mut ::ffi::Display) -> ErrorTrapGuard {
+    let trap = ErrorTrap {
+      display: display as usize,
+      first_serial: ::ffi::XNextRequest(display),
+      error: None,
+    };
+    let guard = ErrorTrapGuard {
+      display: trap.display,
+      first_serial: trap.first_serial,
+      removed: false,
+    };
+    lock(&ERROR_TRAPS).push(trap);
+    return guard;
+  }
+
+  // Nested traps can share a first serial, so this must only run once per guard.
+  fn remove (&mut self) -> Option<ErrorTrap> {
+    if self.removed {
+      return None;
+    }
+    self.removed = true;
+    let mut traps = lock(&ERROR_TRAPS);
+    let index = traps.iter().rposition(|t| t.display == self.display && t.first_serial == self.first_serial);
+    return index.map(|i| traps.remove(i));
+  }
+}
+
+impl Drop for ErrorTrapGuard {
+  fn drop (&mut self) {
+    self.remove();
+  }
+}
+
+static ERROR_TRAPS: Mutex<Vec<ErrorTrap>> = Mutex::new(Vec::new());
+static LOST_CONNECTIONS: Mutex<Vec<(usize, IoError)>> = Mutex::new(Vec::new());
+
+// Xlib callbacks must not panic, so a poisoned lock is used anyway.
+fn lock<T> (mutex: &Mutex<T>) -> MutexGuard<T> {
+  match mutex.lock() {
+    Ok(guard) => guard,
+    Err(poisoned) => poisoned.into_inner(),
+  }
+}
+
+
 //
 // initialize before connecting
 //
 
 
+static mut _threads_init: AtomicBool = ATOMIC_BOOL_INIT;
 static mut _was_init: AtomicBool = ATOMIC_BOOL_INIT;
 
+// Held by init and init_threads, so no display can be opened between init_threads' check and XInitThreads.
+static INIT_LOCK: Mutex<()> = Mutex::new(());
+
 unsafe extern "C"
 fn handle_error (display: *mut ::ffi::Display, event: *const ::ffi::XErrorEvent) -> c_int {
-  let mut error_buf = [0u8; 64];
-  let error_ptr = &mut error_buf[0] as *mut u8 as *mut c_char;
-  ::ffi::XGetErrorText(display, (*event).error_code as c_int, error_ptr, error_buf.len() as c_int);
-  let error_string = String::from_utf8_lossy(&error_buf[]).into_owned();
-  error!("Xlib: Serial {}, Error Code {} ({}), Request Code {}, Minor Code {}, Resource ID {}",
-      (*event).serial, (*event).error_code, error_string, (*event).request_code, (*event).minor_code,
-      (*event).resourceid);
+  let mut text_buf = [0u8; 256];
+  let text_ptr = &mut text_buf[0] as *mut u8 as *mut c_char;
+  ::ffi::XGetErrorText(display, (*event).error_code as c_int, text_ptr, text_buf.len() as c_int);
+  let text_len = ::libc::strlen(text_ptr as *const c_char) as usize;
+  let error = XError {
+    code: FromNative::from_native((*event).error_code),
+    request_code: (*event).request_code as u8,
+    minor_code: (*event).minor_code as u8,
+    resource_id: (*event).resourceid as Xid,
+    serial: (*event).serial as u32,
+    text: String::from_utf8_lossy(&text_buf[..text_len]).into_owned(),
+  };
+
+  // the innermost trap covering this request takes the error
+  let mut traps = lock(&ERROR_TRAPS);
+  for trap in traps.iter_mut().rev() {
+    if trap.display == display as usize && (*event).serial >= trap.first_serial {
+      if trap.error.is_none() {
+        trap.error = Some(error);
+      }
+      return 0;
+    }
+  }
+  drop(traps);
+
+  let handler = lock(&ERROR_HANDLER).clone();
+  if let Some(handler) = handler {
+    let mut handler = lock(&*handler);
+    if catch_unwind(AssertUnwindSafe(|| (*handler)(&error))).is_err() {
+      error!("Xlib: error handler panicked");
+    }
+  } else {
+    error!("Xlib: {}", error);
+  }
   return 0;
 }
 
-#[allow(unused_variables)]
 unsafe extern "C"
 fn handle_io_error (display: *mut ::ffi::Display) -> c_int {
-  panic!("Xlib: A fatal I/O error has occurred!");
+  let error = IoError {
+    errno: ::std::io::Error::last_os_error().raw_os_error().unwrap_or(0),
+  };
+  lock(&LOST_CONNECTIONS).push((display as usize, error));
+
+  let handler = lock(&IO_ERROR_HANDLER).clone();
+  if let Some(handler) = handler {
+    let mut handler = lock(&*handler);
+    if catch_unwind(AssertUnwindSafe(|| (*handler)(&error))).is_err() {
+      error!("Xlib: I/O error handler panicked");
+    }
+  } else {
+    error!("Xlib: {}", error);
+  }
+  return 0;
+}
+
+// Returning from here rather than exiting makes the failed Xlib call return normally. Needs libX11 1.7 or later.
+unsafe extern "C"
+fn handle_io_error_exit (_: *mut ::ffi::Display, _: *mut c_void) {
 }
 
 fn init () {
+  let _guard = lock(&INIT_LOCK);
   unsafe {
-    if !_was_init.swap(false, Ordering::SeqCst) {
+    if !_was_init.swap(true, Ordering::SeqCst) {
       ::ffi::XSetErrorHandler(handle_error);
       ::ffi::XSetIOErrorHandler(handle_io_error);
     }
   }
 }
+
+/** Enables Xlib's thread support, which `SharedDisplay` needs. Must be called before any display is opened;
+ * returns false if that's too late or Xlib was built without thread support. */
+pub fn init_threads () -> bool {
+  let _guard = lock(&INIT_LOCK);
+  unsafe {
+    if _threads_init.load(Ordering::SeqCst) {
+      return true;
+    }
+    if _was_init.load(Ordering::SeqCst) || ::ffi::XInitThreads() == 0 {
+      return false;
+    }
+    _threads_init.store(true, Ordering::SeqCst);
+    return true;
+  }
+}
+
+
+//
+// property chunks
+//
+
+
+// Appends one chunk returned by XGetWindowProperty to the data read so far. Format 32 items arrive as longs.
+// Returns None if the format is invalid or differs from that of the earlier chunks.
+unsafe fn append_property_chunk (data: Option<PropertyData>, format: c_int, ptr: *const c_uchar, nitems: usize)
+    -> Option<PropertyData>
+{
+  let mut data = match (data, format) {
+    (Some(data), _) => data,
+    (None, 8) => PropertyData::Byte(Vec::new()),
+    (None, 16) => PropertyData::Short(Vec::new()),
+    (None, 32) => PropertyData::Long(Vec::new()),
+    (None, _) => { return None; },
+  };
+  if data.format() != format as i32 {
+    return None;
+  }
+  if ptr == null() || nitems == 0 {
+    return Some(data);
+  }
+  match data {
+    PropertyData::Byte(ref mut vec) => {
+      vec.push_all(::std::slice::from_raw_buf(&ptr, nitems));
+    },
+    PropertyData::Short(ref mut vec) => {
+      let const_ptr = ptr as *const c_short;
+      for short in ::std::slice::from_raw_buf(&const_ptr, nitems).iter() {
+        vec.push(*short as u16);
+      }
+    },
+    PropertyData::Long(ref mut vec) => {
+      let const_ptr = ptr as *const c_long;
+      for long in ::std::slice::from_raw_buf(&const_ptr, nitems).iter() {
+        vec.push(*long as u32);
+      }
+    },
+  }
+  return Some(data);
+}
+
+
+//
+// string helpers
+//
+
+
+unsafe fn c_str_to_latin1 (c_str: *const c_char) -> String {
+  if c_str == null() {
+    return String::new();
+  }
+  let byte_ptr = c_str as *const u8;
+  let len = ::libc::strlen(c_str) as usize;
+  return latin1_to_string(::std::slice::from_raw_buf(&byte_ptr, len));
+}
+
+fn host_name () -> Option<String> {
+  unsafe {
+    let mut buf: [c_char; 256] = [0; 256];
+    if ::libc::gethostname(buf.as_mut_ptr(), buf.len() as ::libc::size_t) != 0 {
+      return None;
+    }
+    buf[255] = 0;
+    return Some(c_str_to_latin1(buf.as_ptr()));
+  }
+}
+
+
+#[cfg(test)]
+mod test {
+  use libc::{
+    c_long,
+    c_short,
+    c_uchar,
+  };
+
+  use ::property::PropertyData;
+
+  use super::{
+    append_property_chunk,
+    parse_display_name,
+    DisplayAddress,
+  };
+
+  #[test]
+  fn append_property_chunk_byte () {
+    let first = [b'a', b'b', b'c'];
+    let second = [b'd'];
+    unsafe {
+      let data = append_property_chunk(None, 8, first.as_ptr(), first.len());
+      let data = append_property_chunk(data, 8, second.as_ptr(), second.len());
+      assert!(data == Some(PropertyData::Byte(b"abcd".to_vec())));
+    }
+  }
+
+  #[test]
+  fn append_property_chunk_invalid () {
+    let items: [c_long; 1] = [1];
+    let ptr = items.as_ptr() as *const c_uchar;
+    unsafe {
+      assert!(append_property_chunk(None, 24, ptr, 1) == None);
+      // a chunk whose format differs from the earlier ones is rejected
+      let data = append_property_chunk(None, 32, ptr, 1);
+      assert!(append_property_chunk(data, 16, ptr, 1) == None);
+    }
+  }
+
+  #[test]
+  fn append_property_chunk_long () {
+    // format 32 items are longs in memory, even where long is 64 bits, and only the low 32 bits count
+    let first: [c_long; 2] = [1, -1];
+    let second: [c_long; 1] = [42];
+    unsafe {
+      let data = append_property_chunk(None, 32, first.as_ptr() as *const c_uchar, first.len());
+      let data = append_property_chunk(data, 32, second.as_ptr() as *const c_uchar, second.len());
+      assert!(data == Some(PropertyData::Long(vec![1, 0xffff_ffff, 42])));
+    }
+  }
+
+  #[test]
+  fn append_property_chunk_short () {
+    let items: [c_short; 2] = [7, -1];
+    unsafe {
+      let data = append_property_chunk(None, 16, items.as_ptr() as *const c_uchar, items.len());
+      let data = append_property_chunk(data, 16, items.as_ptr() as *const c_uchar, 0);
+      assert!(data == Some(PropertyData::Short(vec![7, 0xffff])));
+    }
+  }
+
+  #[test]
+  fn parse_display_name_local () {
+    assert_eq!(parse_display_name(":0"), Some(DisplayAddress::Unix(0)));
+    assert_eq!(parse_display_name(":0.1"), Some(DisplayAddress::Unix(0)));
+    assert_eq!(parse_display_name(":12"), Some(DisplayAddress::Unix(12)));
+    assert_eq!(parse_display_name("unix/:0"), Some(DisplayAddress::Unix(0)));
+    assert_eq!(parse_display_name("unix:0"), Some(DisplayAddress::Unix(0)));
+    // local sockets have no port, so large display numbers are fine
+    assert_eq!(parse_display_name(":70000"), Some(DisplayAddress::Unix(70000)));
+  }
+
+  #[test]
+  fn parse_display_name_malformed () {
+    for name in ["", "0", ":", "host", "host:", ":x", ":0.", ":0.x", ":-1", "tcp/host:0x"].iter() {
+      assert_eq!(parse_display_name(name), None);
+    }
+  }
+
+  #[test]
+  fn parse_display_name_other () {
+    assert_eq!(parse_display_name("host::0"), Some(DisplayAddress::Other));
+    assert_eq!(parse_display_name("/private/tmp/launch-x/org.xquartz:0"), Some(DisplayAddress::Other));
+    assert_eq!(parse_display_name("foo/host:0"), Some(DisplayAddress::Other));
+  }
+
+  #[test]
+  fn parse_display_name_tcp () {
+    assert_eq!(parse_display_name("host:0"), Some(DisplayAddress::Tcp("host".to_string(), 6000)));
+    assert_eq!(parse_display_name("host:10.2"), Some(DisplayAddress::Tcp("host".to_string(), 6010)));
+    assert_eq!(parse_display_name("tcp/host:0"), Some(DisplayAddress::Tcp("host".to_string(), 6000)));
+    assert_eq!(parse_display_name("[::1]:0"), Some(DisplayAddress::Tcp("[::1]".to_string(), 6000)));
+    assert_eq!(parse_display_name("host:59535"), Some(DisplayAddress::Tcp("host".to_string(), 65535)));
+    // the port would overflow
+    assert_eq!(parse_display_name("host:59536"), None);
+    assert_eq!(parse_display_name("tcp/:70000"), None);
+    assert_eq!(parse_display_name("host:4294967295"), None);
+  }
+}
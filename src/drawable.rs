@@ -4,8 +4,26 @@
 use ::display::Xid;
 use ::window::Window;
 
-/** Drawable resource identifier type. */
-pub type Drawable = Xid;
+
+//
+// Drawable
+//
+
+
+/** Implemented by resources that can be drawn on: windows and pixmaps. Drawing calls take drawables by value, so
+ * only `Copy` ids qualify; owned handles are passed by reference. */
+pub trait Drawable: Copy {
+  fn drawable_id (&self) -> Xid;
+}
+
+// lets ids be passed by reference too
+impl<'a, D> Drawable for &'a D
+  where D: Drawable
+{
+  fn drawable_id (&self) -> Xid {
+    (**self).drawable_id()
+  }
+}
 
 
 //
@@ -0,0 +1,199 @@
+// Copyright (c) 2015, <daggerbot@gmail.com>
+// All rights reserved.
+
+use std::fmt;
+use std::io;
+
+use libc::c_uchar;
+
+use ::display::Xid;
+use ::internal::{
+  FromNative,
+  ToNative,
+};
+
+
+//
+// ErrorCode
+//
+
+
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub enum ErrorCode {
+  BadRequest,
+  BadValue,
+  BadWindow,
+  BadPixmap,
+  BadAtom,
+  BadCursor,
+  BadFont,
+  BadMatch,
+  BadDrawable,
+  BadAccess,
+  BadAlloc,
+  BadColor,
+  BadGc,
+  BadIdChoice,
+  BadName,
+  BadLength,
+  BadImplementation,
+  Extension(u8),
+}
+
+impl FromNative<c_uchar> for ErrorCode {
+  fn from_native (num: c_uchar) -> ErrorCode {
+    match num {
+      1 => ErrorCode::BadRequest,
+      2 => ErrorCode::BadValue,
+      3 => ErrorCode::BadWindow,
+      4 => ErrorCode::BadPixmap,
+      5 => ErrorCode::BadAtom,
+      6 => ErrorCode::BadCursor,
+      7 => ErrorCode::BadFont,
+      8 => ErrorCode::BadMatch,
+      9 => ErrorCode::BadDrawable,
+      10 => ErrorCode::BadAccess,
+      11 => ErrorCode::BadAlloc,
+      12 => ErrorCode::BadColor,
+      13 => ErrorCode::BadGc,
+      14 => ErrorCode::BadIdChoice,
+      15 => ErrorCode::BadName,
+      16 => ErrorCode::BadLength,
+      17 => ErrorCode::BadImplementation,
+      n => ErrorCode::Extension(n as u8),
+    }
+  }
+}
+
+impl ToNative<c_uchar> for ErrorCode {
+  fn to_native (&self) -> c_uchar {
+    match *self {
+      ErrorCode::BadRequest => 1,
+      ErrorCode::BadValue => 2,
+      ErrorCode::BadWindow => 3,
+      ErrorCode::BadPixmap => 4,
+      ErrorCode::BadAtom => 5,
+      ErrorCode::BadCursor => 6,
+      ErrorCode::BadFont => 7,
+      ErrorCode::BadMatch => 8,
+      ErrorCode::BadDrawable => 9,
+      ErrorCode::BadAccess => 10,
+      ErrorCode::BadAlloc => 11,
+      ErrorCode::BadColor => 12,
+      ErrorCode::BadGc => 13,
+      ErrorCode::BadIdChoice => 14,
+      ErrorCode::BadName => 15,
+      ErrorCode::BadLength => 16,
+      ErrorCode::BadImplementation => 17,
+      ErrorCode::Extension(n) => n as c_uchar,
+    }
+  }
+}
+
+
+//
+// IoError
+//
+
+
+/** A fatal error on the connection to the server, such as the server going away. The `Display` it occurred on
+ * can't be used any further and should be dropped. */
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct IoError {
+  pub errno: i32,
+}
+
+impl fmt::Display for IoError {
+  fn fmt (&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "fatal I/O error on X connection: {}", io::Error::from_raw_os_error(self.errno))
+  }
+}
+
+
+//
+// OpenError
+//
+
+
+/** Reasons why a display connection couldn't be opened. */
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub enum OpenError {
+  /** No display name was given and the `DISPLAY` environment variable is unset or empty. */
+  DisplayUnset,
+  /** The display name isn't of the form `[protocol/][host]:display[.screen]`. */
+  InvalidName,
+  /** The display's host name couldn't be resolved to an address. */
+  HostNotFound,
+  /** Nothing is listening at the display's address. The server may not have started yet. */
+  ConnectionRefused,
+  /** The server accepted the socket connection but refused the client, usually for lack of authorization. */
+  AuthorizationRejected,
+  /** The server's address couldn't be reached. Holds the OS error code. */
+  Io(i32),
+  /** The connection failed for a reason that couldn't be determined. Launchd and DECnet addresses always report
+   * this, since they aren't probed. */
+  Unknown,
+}
+
+impl fmt::Display for OpenError {
+  fn fmt (&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      OpenError::DisplayUnset => write!(f, "DISPLAY is not set"),
+      OpenError::InvalidName => write!(f, "malformed display name"),
+      OpenError::HostNotFound => write!(f, "can't resolve X server host name"),
+      OpenError::ConnectionRefused => write!(f, "connection to X server refused"),
+      OpenError::AuthorizationRejected => write!(f, "X server rejected the client (authorization failed?)"),
+      OpenError::Io(errno) => write!(f, "can't reach X server: {}", io::Error::from_raw_os_error(errno)),
+      OpenError::Unknown => write!(f, "can't open display for an unknown reason"),
+    }
+  }
+}
+
+
+//
+// XError
+//
+
+
+/** A protocol error reported by the server. `serial` identifies the request that caused it. */
+#[derive(Clone)]
+pub struct XError {
+  pub code: ErrorCode,
+  pub request_code: u8,
+  pub minor_code: u8,
+  pub resource_id: Xid,
+  pub serial: u32,
+  pub text: String,
+}
+
+impl fmt::Display for XError {
+  fn fmt (&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "Serial {}, Error Code {} ({}), Request Code {}, Minor Code {}, Resource ID {}",
+        self.serial, self.code.to_native(), self.text, self.request_code, self.minor_code, self.resource_id)
+  }
+}
+
+
+#[cfg(test)]
+mod test {
+  use libc::c_uchar;
+
+  use ::internal::{
+    FromNative,
+    ToNative,
+  };
+
+  use super::ErrorCode;
+
+  #[test]
+  fn error_code () {
+    assert!(ErrorCode::from_native(1) == ErrorCode::BadRequest);
+    assert!(ErrorCode::from_native(3) == ErrorCode::BadWindow);
+    assert!(ErrorCode::from_native(17) == ErrorCode::BadImplementation);
+    assert!(ErrorCode::from_native(128) == ErrorCode::Extension(128));
+    for code in 0..256 {
+      let code = code as c_uchar;
+      assert_eq!(ErrorCode::from_native(code).to_native(), code);
+    }
+  }
+}
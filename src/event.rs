@@ -12,10 +12,15 @@ use libc::{
   c_int,
   c_long,
   c_short,
+  c_uint,
   c_ulong,
 };
 
-use ::display::Atom;
+use ::display::{
+  Atom,
+  Time,
+  Xid,
+};
 use ::internal::{
   FromNative,
   ToNative,
@@ -23,6 +28,120 @@ use ::internal::{
 use ::window::Window;
 
 
+//
+// Button
+//
+
+
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub enum Button {
+  Left,
+  Middle,
+  Right,
+  WheelUp,
+  WheelDown,
+  WheelLeft,
+  WheelRight,
+  Other(u32),
+}
+
+impl FromNative<c_uint> for Button {
+  fn from_native (num: c_uint) -> Button {
+    match num {
+      1 => Button::Left,
+      2 => Button::Middle,
+      3 => Button::Right,
+      4 => Button::WheelUp,
+      5 => Button::WheelDown,
+      6 => Button::WheelLeft,
+      7 => Button::WheelRight,
+      n => Button::Other(n as u32),
+    }
+  }
+}
+
+impl ToNative<c_uint> for Button {
+  fn to_native (&self) -> c_uint {
+    match *self {
+      Button::Left => 1,
+      Button::Middle => 2,
+      Button::Right => 3,
+      Button::WheelUp => 4,
+      Button::WheelDown => 5,
+      Button::WheelLeft => 6,
+      Button::WheelRight => 7,
+      Button::Other(n) => n as c_uint,
+    }
+  }
+}
+
+
+//
+// ButtonEvent
+//
+
+
+#[derive(Clone, Copy)]
+pub struct ButtonEvent {
+  pub serial: u32,
+  pub send_event: bool,
+  pub window: Window,
+  pub root: Window,
+  pub subwindow: Window,
+  pub time: Time,
+  pub x: i32,
+  pub y: i32,
+  pub x_root: i32,
+  pub y_root: i32,
+  pub state: ModifierMask,
+  pub button: Button,
+  pub same_screen: bool,
+}
+
+impl FromNative<::ffi::XButtonEvent> for ButtonEvent {
+  fn from_native (xbutton: ::ffi::XButtonEvent) -> ButtonEvent {
+    ButtonEvent {
+      serial: xbutton.serial as u32,
+      send_event: xbutton.send_event != 0,
+      window: Window(xbutton.window as Xid),
+      root: Window(xbutton.root as Xid),
+      subwindow: Window(xbutton.subwindow as Xid),
+      time: xbutton.time as Time,
+      x: xbutton.x as i32,
+      y: xbutton.y as i32,
+      x_root: xbutton.x_root as i32,
+      y_root: xbutton.y_root as i32,
+      state: FromNative::from_native(xbutton.state),
+      button: FromNative::from_native(xbutton.button),
+      same_screen: xbutton.same_screen != 0,
+    }
+  }
+}
+
+// The event kind is left as ButtonPress; `Event::to_native` overrides it for ButtonRelease.
+impl ToNative<::ffi::XButtonEvent> for ButtonEvent {
+  fn to_native (&self) -> ::ffi::XButtonEvent {
+    ::ffi::XButtonEvent {
+      kind: ::ffi::ButtonPress,
+      serial: self.serial as c_ulong,
+      send_event: if self.send_event {1} else {0},
+      display: null_mut(),
+      window: self.window.0 as c_ulong,
+      root: self.root.0 as c_ulong,
+      subwindow: self.subwindow.0 as c_ulong,
+      time: self.time as c_ulong,
+      x: self.x as c_int,
+      y: self.y as c_int,
+      x_root: self.x_root as c_int,
+      y_root: self.y_root as c_int,
+      state: self.state.to_native(),
+      button: self.button.to_native(),
+      same_screen: if self.same_screen {1} else {0},
+    }
+  }
+}
+
+
 //
 // ClientMessageData
 //
@@ -82,8 +201,8 @@ impl FromNative<::ffi::XClientMessageEvent> for Option<ClientMessageEvent> {
     let e = ClientMessageEvent {
       serial: xclient.serial as u32,
       send_event: xclient.send_event != 0,
-      window: xclient.window as Window,
-      message_type: xclient.message_type as Atom,
+      window: Window(xclient.window as Xid),
+      message_type: Atom(xclient.message_type as Xid),
       data: data,
     };
     return Some(e);
@@ -98,20 +217,23 @@ impl ToNative<::ffi::XEvent> for ClientMessageEvent {
       xclient.serial = self.serial as c_ulong;
       xclient.send_event = if self.send_event {1} else {0};
       xclient.display = null_mut();
-      xclient.window = self.window as c_ulong;
-      xclient.message_type = self.message_type as c_ulong;
+      xclient.window = self.window.0 as c_ulong;
+      xclient.message_type = self.message_type.0 as c_ulong;
       match self.data {
         ClientMessageData::Byte(ref array) => {
+          xclient.format = 8;
           for i in 0..20us {
             xclient.set_byte(i, array[i] as c_char);
           }
         },
         ClientMessageData::Short(ref array) => {
+          xclient.format = 16;
           for i in 0..10us {
             xclient.set_short(i, array[i] as c_short);
           }
         },
         ClientMessageData::Long(ref array) => {
+          xclient.format = 32;
           for i in 0..5us {
             xclient.set_long(i, array[i] as c_long);
           }
@@ -123,6 +245,141 @@ impl ToNative<::ffi::XEvent> for ClientMessageEvent {
 }
 
 
+//
+// ConfigureEvent
+//
+
+
+#[derive(Clone, Copy)]
+pub struct ConfigureEvent {
+  pub serial: u32,
+  pub send_event: bool,
+  pub event: Window,
+  pub window: Window,
+  pub x: i32,
+  pub y: i32,
+  pub width: i32,
+  pub height: i32,
+  pub border_width: i32,
+  pub above: Window,
+  pub override_redirect: bool,
+}
+
+impl FromNative<::ffi::XConfigureEvent> for ConfigureEvent {
+  fn from_native (xconfigure: ::ffi::XConfigureEvent) -> ConfigureEvent {
+    ConfigureEvent {
+      serial: xconfigure.serial as u32,
+      send_event: xconfigure.send_event != 0,
+      event: Window(xconfigure.event as Xid),
+      window: Window(xconfigure.window as Xid),
+      x: xconfigure.x as i32,
+      y: xconfigure.y as i32,
+      width: xconfigure.width as i32,
+      height: xconfigure.height as i32,
+      border_width: xconfigure.border_width as i32,
+      above: Window(xconfigure.above as Xid),
+      override_redirect: xconfigure.override_redirect != 0,
+    }
+  }
+}
+
+impl ToNative<::ffi::XEvent> for ConfigureEvent {
+  fn to_native (&self) -> ::ffi::XEvent {
+    unsafe {
+      let xconfigure = ::ffi::XConfigureEvent {
+        kind: ::ffi::ConfigureNotify,
+        serial: self.serial as c_ulong,
+        send_event: if self.send_event {1} else {0},
+        display: null_mut(),
+        event: self.event.0 as c_ulong,
+        window: self.window.0 as c_ulong,
+        x: self.x as c_int,
+        y: self.y as c_int,
+        width: self.width as c_int,
+        height: self.height as c_int,
+        border_width: self.border_width as c_int,
+        above: self.above.0 as c_ulong,
+        override_redirect: if self.override_redirect {1} else {0},
+      };
+      return reinterpret(&xconfigure);
+    }
+  }
+}
+
+
+//
+// CrossingEvent
+//
+
+
+#[derive(Clone, Copy)]
+pub struct CrossingEvent {
+  pub serial: u32,
+  pub send_event: bool,
+  pub window: Window,
+  pub root: Window,
+  pub subwindow: Window,
+  pub time: Time,
+  pub x: i32,
+  pub y: i32,
+  pub x_root: i32,
+  pub y_root: i32,
+  pub mode: NotifyMode,
+  pub detail: NotifyDetail,
+  pub same_screen: bool,
+  pub focus: bool,
+  pub state: ModifierMask,
+}
+
+impl FromNative<::ffi::XCrossingEvent> for Option<CrossingEvent> {
+  fn from_native (xcrossing: ::ffi::XCrossingEvent) -> Option<CrossingEvent> {
+    let e = CrossingEvent {
+      serial: xcrossing.serial as u32,
+      send_event: xcrossing.send_event != 0,
+      window: Window(xcrossing.window as Xid),
+      root: Window(xcrossing.root as Xid),
+      subwindow: Window(xcrossing.subwindow as Xid),
+      time: xcrossing.time as Time,
+      x: xcrossing.x as i32,
+      y: xcrossing.y as i32,
+      x_root: xcrossing.x_root as i32,
+      y_root: xcrossing.y_root as i32,
+      mode: if let Some(m) = FromNative::from_native(xcrossing.mode) {m} else { return None; },
+      detail: if let Some(d) = FromNative::from_native(xcrossing.detail) {d} else { return None; },
+      same_screen: xcrossing.same_screen != 0,
+      focus: xcrossing.focus != 0,
+      state: FromNative::from_native(xcrossing.state),
+    };
+    return Some(e);
+  }
+}
+
+// The event kind is left as EnterNotify; `Event::to_native` overrides it for LeaveNotify.
+impl ToNative<::ffi::XCrossingEvent> for CrossingEvent {
+  fn to_native (&self) -> ::ffi::XCrossingEvent {
+    ::ffi::XCrossingEvent {
+      kind: ::ffi::EnterNotify,
+      serial: self.serial as c_ulong,
+      send_event: if self.send_event {1} else {0},
+      display: null_mut(),
+      window: self.window.0 as c_ulong,
+      root: self.root.0 as c_ulong,
+      subwindow: self.subwindow.0 as c_ulong,
+      time: self.time as c_ulong,
+      x: self.x as c_int,
+      y: self.y as c_int,
+      x_root: self.x_root as c_int,
+      y_root: self.y_root as c_int,
+      mode: self.mode.to_native(),
+      detail: self.detail.to_native(),
+      same_screen: if self.same_screen {1} else {0},
+      focus: if self.focus {1} else {0},
+      state: self.state.to_native(),
+    }
+  }
+}
+
+
 //
 // DestroyWindowEvent
 //
@@ -141,8 +398,8 @@ impl FromNative<::ffi::XDestroyWindowEvent> for DestroyWindowEvent {
     DestroyWindowEvent {
       serial: xdestroy.serial as u32,
       send_event: xdestroy.send_event != 0,
-      event: xdestroy.event as Window,
-      window: xdestroy.window as Window,
+      event: Window(xdestroy.event as Xid),
+      window: Window(xdestroy.window as Xid),
     }
   }
 }
@@ -155,8 +412,8 @@ impl ToNative<::ffi::XEvent> for DestroyWindowEvent {
         serial: self.serial as c_ulong,
         send_event: if self.send_event {1} else {0},
         display: null_mut(),
-        event: self.event as c_ulong,
-        window: self.window as c_ulong,
+        event: self.event.0 as c_ulong,
+        window: self.window.0 as c_ulong,
       };
       return reinterpret(&xdestroy);
     }
@@ -171,25 +428,82 @@ impl ToNative<::ffi::XEvent> for DestroyWindowEvent {
 
 #[derive(Clone, Copy)]
 pub enum Event {
+  ButtonPress(ButtonEvent),
+  ButtonRelease(ButtonEvent),
   ClientMessage(ClientMessageEvent),
+  Configure(ConfigureEvent),
   DestroyWindow(DestroyWindowEvent),
+  Enter(CrossingEvent),
   Expose(ExposeEvent),
+  FocusIn(FocusChangeEvent),
+  FocusOut(FocusChangeEvent),
+  KeyPress(KeyEvent),
+  KeyRelease(KeyEvent),
+  Leave(CrossingEvent),
+  Map(MapEvent),
+  Motion(MotionEvent),
+  Property(PropertyEvent),
+  Unknown(UnknownEvent),
+  Unmap(UnmapEvent),
 }
 
-impl FromNative<::ffi::XEvent> for Option<Event> {
-  fn from_native (xevent: ::ffi::XEvent) -> Option<Event> {
+impl FromNative<::ffi::XEvent> for Event {
+  fn from_native (xevent: ::ffi::XEvent) -> Event {
     unsafe {
       match xevent.kind() {
+        ::ffi::ButtonPress => Event::ButtonPress(FromNative::from_native(reinterpret(&xevent))),
+        ::ffi::ButtonRelease => Event::ButtonRelease(FromNative::from_native(reinterpret(&xevent))),
         ::ffi::ClientMessage => {
           if let Some(e) = FromNative::from_native(reinterpret(&xevent)) {
-            Some(Event::ClientMessage(e))
+            Event::ClientMessage(e)
           } else {
-            None
+            Event::Unknown(FromNative::from_native(xevent))
           }
         }
-        ::ffi::DestroyNotify => Some(Event::DestroyWindow(FromNative::from_native(reinterpret(&xevent)))),
-        ::ffi::Expose => Some(Event::Expose(FromNative::from_native(reinterpret(&xevent)))),
-        _ => None,
+        ::ffi::ConfigureNotify => Event::Configure(FromNative::from_native(reinterpret(&xevent))),
+        ::ffi::DestroyNotify => Event::DestroyWindow(FromNative::from_native(reinterpret(&xevent))),
+        ::ffi::EnterNotify => {
+          if let Some(e) = FromNative::from_native(reinterpret(&xevent)) {
+            Event::Enter(e)
+          } else {
+            Event::Unknown(FromNative::from_native(xevent))
+          }
+        }
+        ::ffi::Expose => Event::Expose(FromNative::from_native(reinterpret(&xevent))),
+        ::ffi::FocusIn => {
+          if let Some(e) = FromNative::from_native(reinterpret(&xevent)) {
+            Event::FocusIn(e)
+          } else {
+            Event::Unknown(FromNative::from_native(xevent))
+          }
+        }
+        ::ffi::FocusOut => {
+          if let Some(e) = FromNative::from_native(reinterpret(&xevent)) {
+            Event::FocusOut(e)
+          } else {
+            Event::Unknown(FromNative::from_native(xevent))
+          }
+        }
+        ::ffi::KeyPress => Event::KeyPress(FromNative::from_native(reinterpret(&xevent))),
+        ::ffi::KeyRelease => Event::KeyRelease(FromNative::from_native(reinterpret(&xevent))),
+        ::ffi::LeaveNotify => {
+          if let Some(e) = FromNative::from_native(reinterpret(&xevent)) {
+            Event::Leave(e)
+          } else {
+            Event::Unknown(FromNative::from_native(xevent))
+          }
+        }
+        ::ffi::MapNotify => Event::Map(FromNative::from_native(reinterpret(&xevent))),
+        ::ffi::MotionNotify => Event::Motion(FromNative::from_native(reinterpret(&xevent))),
+        ::ffi::PropertyNotify => {
+          if let Some(e) = FromNative::from_native(reinterpret(&xevent)) {
+            Event::Property(e)
+          } else {
+            Event::Unknown(FromNative::from_native(xevent))
+          }
+        }
+        ::ffi::UnmapNotify => Event::Unmap(FromNative::from_native(reinterpret(&xevent))),
+        _ => Event::Unknown(FromNative::from_native(xevent)),
       }
     }
   }
@@ -198,9 +512,23 @@ impl FromNative<::ffi::XEvent> for Option<Event> {
 impl ToNative<::ffi::XEvent> for Event {
   fn to_native (&self) -> ::ffi::XEvent {
     match *self {
+      Event::ButtonPress(ref e) => with_kind(::ffi::ButtonPress, &e.to_native()),
+      Event::ButtonRelease(ref e) => with_kind(::ffi::ButtonRelease, &e.to_native()),
       Event::ClientMessage(ref e) => e.to_native(),
+      Event::Configure(ref e) => e.to_native(),
       Event::DestroyWindow(ref e) => e.to_native(),
+      Event::Enter(ref e) => with_kind(::ffi::EnterNotify, &e.to_native()),
       Event::Expose(ref e) => e.to_native(),
+      Event::FocusIn(ref e) => with_kind(::ffi::FocusIn, &e.to_native()),
+      Event::FocusOut(ref e) => with_kind(::ffi::FocusOut, &e.to_native()),
+      Event::KeyPress(ref e) => with_kind(::ffi::KeyPress, &e.to_native()),
+      Event::KeyRelease(ref e) => with_kind(::ffi::KeyRelease, &e.to_native()),
+      Event::Leave(ref e) => with_kind(::ffi::LeaveNotify, &e.to_native()),
+      Event::Map(ref e) => e.to_native(),
+      Event::Motion(ref e) => e.to_native(),
+      Event::Property(ref e) => e.to_native(),
+      Event::Unknown(ref e) => e.to_native(),
+      Event::Unmap(ref e) => e.to_native(),
     }
   }
 }
@@ -337,6 +665,130 @@ impl ToNative<c_long> for EventMask {
 }
 
 
+//
+// EventType
+//
+
+
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub enum EventType {
+  KeyPress,
+  KeyRelease,
+  ButtonPress,
+  ButtonRelease,
+  MotionNotify,
+  EnterNotify,
+  LeaveNotify,
+  FocusIn,
+  FocusOut,
+  KeymapNotify,
+  Expose,
+  GraphicsExpose,
+  NoExpose,
+  VisibilityNotify,
+  CreateNotify,
+  DestroyNotify,
+  UnmapNotify,
+  MapNotify,
+  MapRequest,
+  ReparentNotify,
+  ConfigureNotify,
+  ConfigureRequest,
+  GravityNotify,
+  ResizeRequest,
+  CirculateNotify,
+  CirculateRequest,
+  PropertyNotify,
+  SelectionClear,
+  SelectionRequest,
+  SelectionNotify,
+  ColormapNotify,
+  ClientMessage,
+  MappingNotify,
+}
+
+impl FromNative<c_int> for Option<EventType> {
+  fn from_native (num: c_int) -> Option<EventType> {
+    match num {
+      ::ffi::KeyPress => Some(EventType::KeyPress),
+      ::ffi::KeyRelease => Some(EventType::KeyRelease),
+      ::ffi::ButtonPress => Some(EventType::ButtonPress),
+      ::ffi::ButtonRelease => Some(EventType::ButtonRelease),
+      ::ffi::MotionNotify => Some(EventType::MotionNotify),
+      ::ffi::EnterNotify => Some(EventType::EnterNotify),
+      ::ffi::LeaveNotify => Some(EventType::LeaveNotify),
+      ::ffi::FocusIn => Some(EventType::FocusIn),
+      ::ffi::FocusOut => Some(EventType::FocusOut),
+      ::ffi::KeymapNotify => Some(EventType::KeymapNotify),
+      ::ffi::Expose => Some(EventType::Expose),
+      ::ffi::GraphicsExpose => Some(EventType::GraphicsExpose),
+      ::ffi::NoExpose => Some(EventType::NoExpose),
+      ::ffi::VisibilityNotify => Some(EventType::VisibilityNotify),
+      ::ffi::CreateNotify => Some(EventType::CreateNotify),
+      ::ffi::DestroyNotify => Some(EventType::DestroyNotify),
+      ::ffi::UnmapNotify => Some(EventType::UnmapNotify),
+      ::ffi::MapNotify => Some(EventType::MapNotify),
+      ::ffi::MapRequest => Some(EventType::MapRequest),
+      ::ffi::ReparentNotify => Some(EventType::ReparentNotify),
+      ::ffi::ConfigureNotify => Some(EventType::ConfigureNotify),
+      ::ffi::ConfigureRequest => Some(EventType::ConfigureRequest),
+      ::ffi::GravityNotify => Some(EventType::GravityNotify),
+      ::ffi::ResizeRequest => Some(EventType::ResizeRequest),
+      ::ffi::CirculateNotify => Some(EventType::CirculateNotify),
+      ::ffi::CirculateRequest => Some(EventType::CirculateRequest),
+      ::ffi::PropertyNotify => Some(EventType::PropertyNotify),
+      ::ffi::SelectionClear => Some(EventType::SelectionClear),
+      ::ffi::SelectionRequest => Some(EventType::SelectionRequest),
+      ::ffi::SelectionNotify => Some(EventType::SelectionNotify),
+      ::ffi::ColormapNotify => Some(EventType::ColormapNotify),
+      ::ffi::ClientMessage => Some(EventType::ClientMessage),
+      ::ffi::MappingNotify => Some(EventType::MappingNotify),
+      _ => None,
+    }
+  }
+}
+
+impl ToNative<c_int> for EventType {
+  fn to_native (&self) -> c_int {
+    match *self {
+      EventType::KeyPress => ::ffi::KeyPress,
+      EventType::KeyRelease => ::ffi::KeyRelease,
+      EventType::ButtonPress => ::ffi::ButtonPress,
+      EventType::ButtonRelease => ::ffi::ButtonRelease,
+      EventType::MotionNotify => ::ffi::MotionNotify,
+      EventType::EnterNotify => ::ffi::EnterNotify,
+      EventType::LeaveNotify => ::ffi::LeaveNotify,
+      EventType::FocusIn => ::ffi::FocusIn,
+      EventType::FocusOut => ::ffi::FocusOut,
+      EventType::KeymapNotify => ::ffi::KeymapNotify,
+      EventType::Expose => ::ffi::Expose,
+      EventType::GraphicsExpose => ::ffi::GraphicsExpose,
+      EventType::NoExpose => ::ffi::NoExpose,
+      EventType::VisibilityNotify => ::ffi::VisibilityNotify,
+      EventType::CreateNotify => ::ffi::CreateNotify,
+      EventType::DestroyNotify => ::ffi::DestroyNotify,
+      EventType::UnmapNotify => ::ffi::UnmapNotify,
+      EventType::MapNotify => ::ffi::MapNotify,
+      EventType::MapRequest => ::ffi::MapRequest,
+      EventType::ReparentNotify => ::ffi::ReparentNotify,
+      EventType::ConfigureNotify => ::ffi::ConfigureNotify,
+      EventType::ConfigureRequest => ::ffi::ConfigureRequest,
+      EventType::GravityNotify => ::ffi::GravityNotify,
+      EventType::ResizeRequest => ::ffi::ResizeRequest,
+      EventType::CirculateNotify => ::ffi::CirculateNotify,
+      EventType::CirculateRequest => ::ffi::CirculateRequest,
+      EventType::PropertyNotify => ::ffi::PropertyNotify,
+      EventType::SelectionClear => ::ffi::SelectionClear,
+      EventType::SelectionRequest => ::ffi::SelectionRequest,
+      EventType::SelectionNotify => ::ffi::SelectionNotify,
+      EventType::ColormapNotify => ::ffi::ColormapNotify,
+      EventType::ClientMessage => ::ffi::ClientMessage,
+      EventType::MappingNotify => ::ffi::MappingNotify,
+    }
+  }
+}
+
+
 //
 // ExposeEvent
 //
@@ -359,7 +811,7 @@ impl FromNative<::ffi::XExposeEvent> for ExposeEvent {
     ExposeEvent {
       serial: xexpose.serial as u32,
       send_event: xexpose.send_event != 0,
-      window: xexpose.window as Window,
+      window: Window(xexpose.window as Xid),
       x: xexpose.x as i32,
       y: xexpose.y as i32,
       width: xexpose.width as i32,
@@ -377,7 +829,7 @@ impl ToNative<::ffi::XEvent> for ExposeEvent {
         serial: self.serial as c_ulong,
         send_event: if self.send_event {1} else {0},
         display: null_mut(),
-        window: self.window as c_ulong,
+        window: self.window.0 as c_ulong,
         x: self.x as c_int,
         y: self.y as c_int,
         width: self.width as c_int,
@@ -390,6 +842,580 @@ impl ToNative<::ffi::XEvent> for ExposeEvent {
 }
 
 
+//
+// FocusChangeEvent
+//
+
+
+#[derive(Clone, Copy)]
+pub struct FocusChangeEvent {
+  pub serial: u32,
+  pub send_event: bool,
+  pub window: Window,
+  pub mode: NotifyMode,
+  pub detail: NotifyDetail,
+}
+
+impl FromNative<::ffi::XFocusChangeEvent> for Option<FocusChangeEvent> {
+  fn from_native (xfocus: ::ffi::XFocusChangeEvent) -> Option<FocusChangeEvent> {
+    let e = FocusChangeEvent {
+      serial: xfocus.serial as u32,
+      send_event: xfocus.send_event != 0,
+      window: Window(xfocus.window as Xid),
+      mode: if let Some(m) = FromNative::from_native(xfocus.mode) {m} else { return None; },
+      detail: if let Some(d) = FromNative::from_native(xfocus.detail) {d} else { return None; },
+    };
+    return Some(e);
+  }
+}
+
+// The event kind is left as FocusIn; `Event::to_native` overrides it for FocusOut.
+impl ToNative<::ffi::XFocusChangeEvent> for FocusChangeEvent {
+  fn to_native (&self) -> ::ffi::XFocusChangeEvent {
+    ::ffi::XFocusChangeEvent {
+      kind: ::ffi::FocusIn,
+      serial: self.serial as c_ulong,
+      send_event: if self.send_event {1} else {0},
+      display: null_mut(),
+      window: self.window.0 as c_ulong,
+      mode: self.mode.to_native(),
+      detail: self.detail.to_native(),
+    }
+  }
+}
+
+
+//
+// KeyEvent
+//
+
+
+#[derive(Clone, Copy)]
+pub struct KeyEvent {
+  pub serial: u32,
+  pub send_event: bool,
+  pub window: Window,
+  pub root: Window,
+  pub subwindow: Window,
+  pub time: Time,
+  pub x: i32,
+  pub y: i32,
+  pub x_root: i32,
+  pub y_root: i32,
+  pub state: ModifierMask,
+  pub keycode: u32,
+  pub same_screen: bool,
+}
+
+impl FromNative<::ffi::XKeyEvent> for KeyEvent {
+  fn from_native (xkey: ::ffi::XKeyEvent) -> KeyEvent {
+    KeyEvent {
+      serial: xkey.serial as u32,
+      send_event: xkey.send_event != 0,
+      window: Window(xkey.window as Xid),
+      root: Window(xkey.root as Xid),
+      subwindow: Window(xkey.subwindow as Xid),
+      time: xkey.time as Time,
+      x: xkey.x as i32,
+      y: xkey.y as i32,
+      x_root: xkey.x_root as i32,
+      y_root: xkey.y_root as i32,
+      state: FromNative::from_native(xkey.state),
+      keycode: xkey.keycode as u32,
+      same_screen: xkey.same_screen != 0,
+    }
+  }
+}
+
+// The event kind is left as KeyPress; `Event::to_native` overrides it for KeyRelease.
+impl ToNative<::ffi::XKeyEvent> for KeyEvent {
+  fn to_native (&self) -> ::ffi::XKeyEvent {
+    ::ffi::XKeyEvent {
+      kind: ::ffi::KeyPress,
+      serial: self.serial as c_ulong,
+      send_event: if self.send_event {1} else {0},
+      display: null_mut(),
+      window: self.window.0 as c_ulong,
+      root: self.root.0 as c_ulong,
+      subwindow: self.subwindow.0 as c_ulong,
+      time: self.time as c_ulong,
+      x: self.x as c_int,
+      y: self.y as c_int,
+      x_root: self.x_root as c_int,
+      y_root: self.y_root as c_int,
+      state: self.state.to_native(),
+      keycode: self.keycode as c_uint,
+      same_screen: if self.same_screen {1} else {0},
+    }
+  }
+}
+
+
+//
+// MapEvent
+//
+
+
+#[derive(Clone, Copy)]
+pub struct MapEvent {
+  pub serial: u32,
+  pub send_event: bool,
+  pub event: Window,
+  pub window: Window,
+  pub override_redirect: bool,
+}
+
+impl FromNative<::ffi::XMapEvent> for MapEvent {
+  fn from_native (xmap: ::ffi::XMapEvent) -> MapEvent {
+    MapEvent {
+      serial: xmap.serial as u32,
+      send_event: xmap.send_event != 0,
+      event: Window(xmap.event as Xid),
+      window: Window(xmap.window as Xid),
+      override_redirect: xmap.override_redirect != 0,
+    }
+  }
+}
+
+impl ToNative<::ffi::XEvent> for MapEvent {
+  fn to_native (&self) -> ::ffi::XEvent {
+    unsafe {
+      let xmap = ::ffi::XMapEvent {
+        kind: ::ffi::MapNotify,
+        serial: self.serial as c_ulong,
+        send_event: if self.send_event {1} else {0},
+        display: null_mut(),
+        event: self.event.0 as c_ulong,
+        window: self.window.0 as c_ulong,
+        override_redirect: if self.override_redirect {1} else {0},
+      };
+      return reinterpret(&xmap);
+    }
+  }
+}
+
+
+//
+// ModifierMask
+//
+
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct ModifierMask {
+  pub shift: bool,
+  pub lock: bool,
+  pub control: bool,
+  pub mod1: bool,
+  pub mod2: bool,
+  pub mod3: bool,
+  pub mod4: bool,
+  pub mod5: bool,
+  pub button1: bool,
+  pub button2: bool,
+  pub button3: bool,
+  pub button4: bool,
+  pub button5: bool,
+}
+
+impl ModifierMask {
+  pub fn empty () -> ModifierMask {
+    ModifierMask {
+      shift: false,
+      lock: false,
+      control: false,
+      mod1: false,
+      mod2: false,
+      mod3: false,
+      mod4: false,
+      mod5: false,
+      button1: false,
+      button2: false,
+      button3: false,
+      button4: false,
+      button5: false,
+    }
+  }
+}
+
+impl FromNative<c_uint> for ModifierMask {
+  fn from_native (mask: c_uint) -> ModifierMask {
+    ModifierMask {
+      shift: mask & 0x0001 != 0,
+      lock: mask & 0x0002 != 0,
+      control: mask & 0x0004 != 0,
+      mod1: mask & 0x0008 != 0,
+      mod2: mask & 0x0010 != 0,
+      mod3: mask & 0x0020 != 0,
+      mod4: mask & 0x0040 != 0,
+      mod5: mask & 0x0080 != 0,
+      button1: mask & 0x0100 != 0,
+      button2: mask & 0x0200 != 0,
+      button3: mask & 0x0400 != 0,
+      button4: mask & 0x0800 != 0,
+      button5: mask & 0x1000 != 0,
+    }
+  }
+}
+
+impl ToNative<c_uint> for ModifierMask {
+  fn to_native (&self) -> c_uint {
+    let mut ord: c_uint = 0;
+    if self.shift { ord |= 0x0001; }
+    if self.lock { ord |= 0x0002; }
+    if self.control { ord |= 0x0004; }
+    if self.mod1 { ord |= 0x0008; }
+    if self.mod2 { ord |= 0x0010; }
+    if self.mod3 { ord |= 0x0020; }
+    if self.mod4 { ord |= 0x0040; }
+    if self.mod5 { ord |= 0x0080; }
+    if self.button1 { ord |= 0x0100; }
+    if self.button2 { ord |= 0x0200; }
+    if self.button3 { ord |= 0x0400; }
+    if self.button4 { ord |= 0x0800; }
+    if self.button5 { ord |= 0x1000; }
+    return ord;
+  }
+}
+
+
+//
+// MotionEvent
+//
+
+
+#[derive(Clone, Copy)]
+pub struct MotionEvent {
+  pub serial: u32,
+  pub send_event: bool,
+  pub window: Window,
+  pub root: Window,
+  pub subwindow: Window,
+  pub time: Time,
+  pub x: i32,
+  pub y: i32,
+  pub x_root: i32,
+  pub y_root: i32,
+  pub state: ModifierMask,
+  pub is_hint: bool,
+  pub same_screen: bool,
+}
+
+impl FromNative<::ffi::XMotionEvent> for MotionEvent {
+  fn from_native (xmotion: ::ffi::XMotionEvent) -> MotionEvent {
+    MotionEvent {
+      serial: xmotion.serial as u32,
+      send_event: xmotion.send_event != 0,
+      window: Window(xmotion.window as Xid),
+      root: Window(xmotion.root as Xid),
+      subwindow: Window(xmotion.subwindow as Xid),
+      time: xmotion.time as Time,
+      x: xmotion.x as i32,
+      y: xmotion.y as i32,
+      x_root: xmotion.x_root as i32,
+      y_root: xmotion.y_root as i32,
+      state: FromNative::from_native(xmotion.state),
+      is_hint: xmotion.is_hint != 0,
+      same_screen: xmotion.same_screen != 0,
+    }
+  }
+}
+
+impl ToNative<::ffi::XEvent> for MotionEvent {
+  fn to_native (&self) -> ::ffi::XEvent {
+    unsafe {
+      let xmotion = ::ffi::XMotionEvent {
+        kind: ::ffi::MotionNotify,
+        serial: self.serial as c_ulong,
+        send_event: if self.send_event {1} else {0},
+        display: null_mut(),
+        window: self.window.0 as c_ulong,
+        root: self.root.0 as c_ulong,
+        subwindow: self.subwindow.0 as c_ulong,
+        time: self.time as c_ulong,
+        x: self.x as c_int,
+        y: self.y as c_int,
+        x_root: self.x_root as c_int,
+        y_root: self.y_root as c_int,
+        state: self.state.to_native(),
+        is_hint: if self.is_hint {1} else {0},
+        same_screen: if self.same_screen {1} else {0},
+      };
+      return reinterpret(&xmotion);
+    }
+  }
+}
+
+
+//
+// NotifyDetail
+//
+
+
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub enum NotifyDetail {
+  Ancestor,
+  Virtual,
+  Inferior,
+  Nonlinear,
+  NonlinearVirtual,
+  Pointer,
+  PointerRoot,
+  None,
+}
+
+impl FromNative<c_int> for Option<NotifyDetail> {
+  fn from_native (num: c_int) -> Option<NotifyDetail> {
+    match num {
+      0 => Some(NotifyDetail::Ancestor),
+      1 => Some(NotifyDetail::Virtual),
+      2 => Some(NotifyDetail::Inferior),
+      3 => Some(NotifyDetail::Nonlinear),
+      4 => Some(NotifyDetail::NonlinearVirtual),
+      5 => Some(NotifyDetail::Pointer),
+      6 => Some(NotifyDetail::PointerRoot),
+      7 => Some(NotifyDetail::None),
+      _ => None,
+    }
+  }
+}
+
+impl ToNative<c_int> for NotifyDetail {
+  fn to_native (&self) -> c_int {
+    match *self {
+      NotifyDetail::Ancestor => 0,
+      NotifyDetail::Virtual => 1,
+      NotifyDetail::Inferior => 2,
+      NotifyDetail::Nonlinear => 3,
+      NotifyDetail::NonlinearVirtual => 4,
+      NotifyDetail::Pointer => 5,
+      NotifyDetail::PointerRoot => 6,
+      NotifyDetail::None => 7,
+    }
+  }
+}
+
+
+//
+// NotifyMode
+//
+
+
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub enum NotifyMode {
+  Normal,
+  Grab,
+  Ungrab,
+  WhileGrabbed,
+}
+
+impl FromNative<c_int> for Option<NotifyMode> {
+  fn from_native (num: c_int) -> Option<NotifyMode> {
+    match num {
+      0 => Some(NotifyMode::Normal),
+      1 => Some(NotifyMode::Grab),
+      2 => Some(NotifyMode::Ungrab),
+      3 => Some(NotifyMode::WhileGrabbed),
+      _ => None,
+    }
+  }
+}
+
+impl ToNative<c_int> for NotifyMode {
+  fn to_native (&self) -> c_int {
+    match *self {
+      NotifyMode::Normal => 0,
+      NotifyMode::Grab => 1,
+      NotifyMode::Ungrab => 2,
+      NotifyMode::WhileGrabbed => 3,
+    }
+  }
+}
+
+
+//
+// PropertyEvent
+//
+
+
+#[derive(Clone, Copy)]
+pub struct PropertyEvent {
+  pub serial: u32,
+  pub send_event: bool,
+  pub window: Window,
+  pub atom: Atom,
+  pub time: Time,
+  pub state: PropertyState,
+}
+
+impl FromNative<::ffi::XPropertyEvent> for Option<PropertyEvent> {
+  fn from_native (xproperty: ::ffi::XPropertyEvent) -> Option<PropertyEvent> {
+    let e = PropertyEvent {
+      serial: xproperty.serial as u32,
+      send_event: xproperty.send_event != 0,
+      window: Window(xproperty.window as Xid),
+      atom: Atom(xproperty.atom as Xid),
+      time: xproperty.time as Time,
+      state: if let Some(s) = FromNative::from_native(xproperty.state) {s} else { return None; },
+    };
+    return Some(e);
+  }
+}
+
+impl ToNative<::ffi::XEvent> for PropertyEvent {
+  fn to_native (&self) -> ::ffi::XEvent {
+    unsafe {
+      let xproperty = ::ffi::XPropertyEvent {
+        kind: ::ffi::PropertyNotify,
+        serial: self.serial as c_ulong,
+        send_event: if self.send_event {1} else {0},
+        display: null_mut(),
+        window: self.window.0 as c_ulong,
+        atom: self.atom.0 as c_ulong,
+        time: self.time as c_ulong,
+        state: self.state.to_native(),
+      };
+      return reinterpret(&xproperty);
+    }
+  }
+}
+
+
+//
+// PropertyState
+//
+
+
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub enum PropertyState {
+  NewValue,
+  Deleted,
+}
+
+impl FromNative<c_int> for Option<PropertyState> {
+  fn from_native (num: c_int) -> Option<PropertyState> {
+    match num {
+      0 => Some(PropertyState::NewValue),
+      1 => Some(PropertyState::Deleted),
+      _ => None,
+    }
+  }
+}
+
+impl ToNative<c_int> for PropertyState {
+  fn to_native (&self) -> c_int {
+    match *self {
+      PropertyState::NewValue => 0,
+      PropertyState::Deleted => 1,
+    }
+  }
+}
+
+
+//
+// UnknownEvent
+//
+
+
+/** Any event not modeled by this crate, including extension events. The native event is kept intact, so its
+ * header fields are read-only. */
+#[derive(Clone, Copy)]
+pub struct UnknownEvent {
+  raw: ::ffi::XEvent,
+}
+
+impl UnknownEvent {
+  pub fn bytes (&self) -> &[u8] {
+    unsafe {
+      let ptr = &self.raw as *const ::ffi::XEvent as *const u8;
+      return ::std::slice::from_raw_buf(&ptr, size_of::<::ffi::XEvent>());
+    }
+  }
+
+  pub fn kind (&self) -> i32 {
+    self.raw.kind() as i32
+  }
+
+  pub fn send_event (&self) -> bool {
+    unsafe {
+      let xany: ::ffi::XAnyEvent = reinterpret(&self.raw);
+      return xany.send_event != 0;
+    }
+  }
+
+  pub fn serial (&self) -> u32 {
+    unsafe {
+      let xany: ::ffi::XAnyEvent = reinterpret(&self.raw);
+      return xany.serial as u32;
+    }
+  }
+
+  pub fn window (&self) -> Window {
+    unsafe {
+      let xany: ::ffi::XAnyEvent = reinterpret(&self.raw);
+      return Window(xany.window as Xid);
+    }
+  }
+}
+
+impl FromNative<::ffi::XEvent> for UnknownEvent {
+  fn from_native (xevent: ::ffi::XEvent) -> UnknownEvent {
+    UnknownEvent {
+      raw: xevent,
+    }
+  }
+}
+
+impl ToNative<::ffi::XEvent> for UnknownEvent {
+  fn to_native (&self) -> ::ffi::XEvent {
+    self.raw
+  }
+}
+
+
+//
+// UnmapEvent
+//
+
+
+#[derive(Clone, Copy)]
+pub struct UnmapEvent {
+  pub serial: u32,
+  pub send_event: bool,
+  pub event: Window,
+  pub window: Window,
+  pub from_configure: bool,
+}
+
+impl FromNative<::ffi::XUnmapEvent> for UnmapEvent {
+  fn from_native (xunmap: ::ffi::XUnmapEvent) -> UnmapEvent {
+    UnmapEvent {
+      serial: xunmap.serial as u32,
+      send_event: xunmap.send_event != 0,
+      event: Window(xunmap.event as Xid),
+      window: Window(xunmap.window as Xid),
+      from_configure: xunmap.from_configure != 0,
+    }
+  }
+}
+
+impl ToNative<::ffi::XEvent> for UnmapEvent {
+  fn to_native (&self) -> ::ffi::XEvent {
+    unsafe {
+      let xunmap = ::ffi::XUnmapEvent {
+        kind: ::ffi::UnmapNotify,
+        serial: self.serial as c_ulong,
+        send_event: if self.send_event {1} else {0},
+        display: null_mut(),
+        event: self.event.0 as c_ulong,
+        window: self.window.0 as c_ulong,
+        from_configure: if self.from_configure {1} else {0},
+      };
+      return reinterpret(&xunmap);
+    }
+  }
+}
+
+
 //
 // private functions
 //
@@ -406,3 +1432,380 @@ unsafe fn reinterpret<I, O> (input: &I) -> O
     return output;
   }
 }
+
+fn with_kind<T> (kind: c_int, input: &T) -> ::ffi::XEvent
+  where T: Copy + Sized
+{
+  unsafe {
+    let mut xevent: ::ffi::XEvent = reinterpret(input);
+    xevent.set_kind(kind);
+    return xevent;
+  }
+}
+
+
+#[cfg(test)]
+mod test {
+  use std::mem::zeroed;
+
+  use ::display::Atom;
+  use ::internal::{
+    FromNative,
+    ToNative,
+  };
+  use ::window::Window;
+
+  use super::{
+    reinterpret,
+    Button,
+    ButtonEvent,
+    ClientMessageData,
+    ClientMessageEvent,
+    ConfigureEvent,
+    CrossingEvent,
+    Event,
+    EventMask,
+    FocusChangeEvent,
+    KeyEvent,
+    MapEvent,
+    ModifierMask,
+    MotionEvent,
+    NotifyDetail,
+    NotifyMode,
+    PropertyEvent,
+    PropertyState,
+    UnmapEvent,
+  };
+
+  fn round_trip (event: Event) -> Event {
+    let xevent: ::ffi::XEvent = event.to_native();
+    return FromNative::from_native(xevent);
+  }
+
+  fn shift_and_button1 () -> ModifierMask {
+    let mut state = ModifierMask::empty();
+    state.shift = true;
+    state.button1 = true;
+    return state;
+  }
+
+  #[test]
+  fn button_events () {
+    let press = ButtonEvent {
+      serial: 3,
+      send_event: false,
+      window: Window(10),
+      root: Window(1),
+      subwindow: Window(11),
+      time: 1234,
+      x: 5,
+      y: -6,
+      x_root: 105,
+      y_root: 94,
+      state: shift_and_button1(),
+      button: Button::Other(9),
+      same_screen: true,
+    };
+    match round_trip(Event::ButtonPress(press)) {
+      Event::ButtonPress(e) => {
+        assert_eq!((e.serial, e.window, e.root, e.subwindow, e.time), (3, Window(10), Window(1), Window(11), 1234));
+        assert_eq!((e.x, e.y, e.x_root, e.y_root), (5, -6, 105, 94));
+        assert!(e.state == shift_and_button1());
+        assert!(e.button == Button::Other(9));
+        assert!(e.same_screen);
+      },
+      _ => panic!("expected ButtonPress"),
+    }
+    let mut release = press;
+    release.button = Button::WheelDown;
+    match round_trip(Event::ButtonRelease(release)) {
+      Event::ButtonRelease(e) => assert!(e.button == Button::WheelDown),
+      _ => panic!("expected ButtonRelease"),
+    }
+  }
+
+  fn client_message_round_trip (data: ClientMessageData) -> ClientMessageData {
+    let event = ClientMessageEvent {
+      serial: 7,
+      send_event: true,
+      window: Window(0x0040_0001),
+      message_type: Atom(301),
+      data: data,
+    };
+    let xevent: ::ffi::XEvent = event.to_native();
+    match FromNative::from_native(xevent) {
+      Event::ClientMessage(e) => {
+        assert_eq!(e.serial, 7);
+        assert!(e.send_event);
+        assert_eq!(e.window, Window(0x0040_0001));
+        assert_eq!(e.message_type, Atom(301));
+        return e.data;
+      },
+      _ => panic!("client message didn't survive the round trip"),
+    }
+  }
+
+  #[test]
+  fn client_message_byte () {
+    let mut array = [0u8; 20];
+    for i in 0..20 {
+      array[i] = 200 + i as u8;
+    }
+    match client_message_round_trip(ClientMessageData::Byte(array)) {
+      ClientMessageData::Byte(data) => assert_eq!(data, array),
+      _ => panic!("expected format 8"),
+    }
+  }
+
+  #[test]
+  fn client_message_long () {
+    let array = [1, 2, 0x7fff_ffff, 0x8000_0000, 0xffff_ffff];
+    match client_message_round_trip(ClientMessageData::Long(array)) {
+      ClientMessageData::Long(data) => assert_eq!(data, array),
+      _ => panic!("expected format 32"),
+    }
+  }
+
+  #[test]
+  fn client_message_short () {
+    let array = [1, 2, 3, 4, 5, 0x7fff, 0x8000, 0xfffe, 0xffff, 0];
+    match client_message_round_trip(ClientMessageData::Short(array)) {
+      ClientMessageData::Short(data) => assert_eq!(data, array),
+      _ => panic!("expected format 16"),
+    }
+  }
+
+  #[test]
+  fn configure_event () {
+    let event = ConfigureEvent {
+      serial: 4,
+      send_event: true,
+      event: Window(1),
+      window: Window(12),
+      x: -20,
+      y: 30,
+      width: 640,
+      height: 480,
+      border_width: 2,
+      above: Window(13),
+      override_redirect: true,
+    };
+    match round_trip(Event::Configure(event)) {
+      Event::Configure(e) => {
+        assert_eq!((e.serial, e.event, e.window, e.above), (4, Window(1), Window(12), Window(13)));
+        assert_eq!((e.x, e.y, e.width, e.height, e.border_width), (-20, 30, 640, 480, 2));
+        assert!(e.send_event && e.override_redirect);
+      },
+      _ => panic!("expected Configure"),
+    }
+  }
+
+  #[test]
+  fn crossing_events () {
+    let enter = CrossingEvent {
+      serial: 5,
+      send_event: false,
+      window: Window(14),
+      root: Window(1),
+      subwindow: Window(0),
+      time: 99,
+      x: 1,
+      y: 2,
+      x_root: 3,
+      y_root: 4,
+      mode: NotifyMode::Grab,
+      detail: NotifyDetail::NonlinearVirtual,
+      same_screen: true,
+      focus: true,
+      state: shift_and_button1(),
+    };
+    match round_trip(Event::Enter(enter)) {
+      Event::Enter(e) => {
+        assert_eq!((e.serial, e.window, e.root, e.subwindow, e.time), (5, Window(14), Window(1), Window(0), 99));
+        assert_eq!((e.x, e.y, e.x_root, e.y_root), (1, 2, 3, 4));
+        assert!(e.mode == NotifyMode::Grab && e.detail == NotifyDetail::NonlinearVirtual);
+        assert!(e.same_screen && e.focus);
+        assert!(e.state == shift_and_button1());
+      },
+      _ => panic!("expected Enter"),
+    }
+    match round_trip(Event::Leave(enter)) {
+      Event::Leave(e) => assert_eq!(e.window, Window(14)),
+      _ => panic!("expected Leave"),
+    }
+
+    // an out-of-range mode can't be decoded, so the event is kept as unknown
+    let mut xcrossing: ::ffi::XCrossingEvent = enter.to_native();
+    xcrossing.mode = 9;
+    let xevent: ::ffi::XEvent = unsafe { reinterpret(&xcrossing) };
+    match FromNative::from_native(xevent) {
+      Event::Unknown(e) => assert_eq!(e.kind(), ::ffi::EnterNotify as i32),
+      _ => panic!("expected Unknown"),
+    }
+  }
+
+  #[test]
+  fn event_mask () {
+    let all: ::libc::c_long = 0x01ff_ffff;
+    let mask: EventMask = FromNative::from_native(all);
+    assert!(mask.key_press && mask.property_change && mask.owner_grab_button);
+    assert_eq!(mask.to_native(), all);
+    assert_eq!(EventMask::empty().to_native(), 0);
+  }
+
+  #[test]
+  fn focus_change_events () {
+    let focus = FocusChangeEvent {
+      serial: 6,
+      send_event: false,
+      window: Window(15),
+      mode: NotifyMode::WhileGrabbed,
+      detail: NotifyDetail::PointerRoot,
+    };
+    match round_trip(Event::FocusIn(focus)) {
+      Event::FocusIn(e) => {
+        assert_eq!((e.serial, e.window), (6, Window(15)));
+        assert!(e.mode == NotifyMode::WhileGrabbed && e.detail == NotifyDetail::PointerRoot);
+      },
+      _ => panic!("expected FocusIn"),
+    }
+    match round_trip(Event::FocusOut(focus)) {
+      Event::FocusOut(e) => assert_eq!(e.window, Window(15)),
+      _ => panic!("expected FocusOut"),
+    }
+  }
+
+  #[test]
+  fn key_events () {
+    let press = KeyEvent {
+      serial: 7,
+      send_event: true,
+      window: Window(16),
+      root: Window(1),
+      subwindow: Window(17),
+      time: 4321,
+      x: 10,
+      y: 20,
+      x_root: 110,
+      y_root: 120,
+      state: shift_and_button1(),
+      keycode: 38,
+      same_screen: false,
+    };
+    match round_trip(Event::KeyPress(press)) {
+      Event::KeyPress(e) => {
+        assert_eq!((e.serial, e.window, e.root, e.subwindow, e.time), (7, Window(16), Window(1), Window(17), 4321));
+        assert_eq!((e.x, e.y, e.x_root, e.y_root, e.keycode), (10, 20, 110, 120, 38));
+        assert!(e.send_event && !e.same_screen);
+        assert!(e.state == shift_and_button1());
+      },
+      _ => panic!("expected KeyPress"),
+    }
+    match round_trip(Event::KeyRelease(press)) {
+      Event::KeyRelease(e) => assert_eq!(e.keycode, 38),
+      _ => panic!("expected KeyRelease"),
+    }
+  }
+
+  #[test]
+  fn map_and_unmap_events () {
+    let map = MapEvent {
+      serial: 8,
+      send_event: false,
+      event: Window(1),
+      window: Window(18),
+      override_redirect: true,
+    };
+    match round_trip(Event::Map(map)) {
+      Event::Map(e) => {
+        assert_eq!((e.serial, e.event, e.window), (8, Window(1), Window(18)));
+        assert!(e.override_redirect);
+      },
+      _ => panic!("expected Map"),
+    }
+    let unmap = UnmapEvent {
+      serial: 9,
+      send_event: false,
+      event: Window(1),
+      window: Window(18),
+      from_configure: true,
+    };
+    match round_trip(Event::Unmap(unmap)) {
+      Event::Unmap(e) => {
+        assert_eq!((e.serial, e.event, e.window), (9, Window(1), Window(18)));
+        assert!(e.from_configure);
+      },
+      _ => panic!("expected Unmap"),
+    }
+  }
+
+  #[test]
+  fn modifier_mask () {
+    let all: ::libc::c_uint = 0x1fff;
+    let mask: ModifierMask = FromNative::from_native(all);
+    assert!(mask.shift && mask.mod5 && mask.button5);
+    assert_eq!(mask.to_native(), all);
+    assert_eq!(ModifierMask::empty().to_native(), 0);
+  }
+
+  #[test]
+  fn motion_event () {
+    let event = MotionEvent {
+      serial: 10,
+      send_event: false,
+      window: Window(19),
+      root: Window(1),
+      subwindow: Window(0),
+      time: 777,
+      x: 30,
+      y: 40,
+      x_root: 130,
+      y_root: 140,
+      state: shift_and_button1(),
+      is_hint: true,
+      same_screen: true,
+    };
+    match round_trip(Event::Motion(event)) {
+      Event::Motion(e) => {
+        assert_eq!((e.serial, e.window, e.time), (10, Window(19), 777));
+        assert_eq!((e.x, e.y, e.x_root, e.y_root), (30, 40, 130, 140));
+        assert!(e.is_hint && e.same_screen);
+        assert!(e.state == shift_and_button1());
+      },
+      _ => panic!("expected Motion"),
+    }
+  }
+
+  #[test]
+  fn property_event () {
+    let event = PropertyEvent {
+      serial: 11,
+      send_event: false,
+      window: Window(20),
+      atom: Atom(39),
+      time: 888,
+      state: PropertyState::Deleted,
+    };
+    match round_trip(Event::Property(event)) {
+      Event::Property(e) => {
+        assert_eq!((e.serial, e.window, e.atom, e.time), (11, Window(20), Atom(39), 888));
+        assert!(e.state == PropertyState::Deleted);
+      },
+      _ => panic!("expected Property"),
+    }
+  }
+
+  #[test]
+  fn unknown_event () {
+    let mut xevent: ::ffi::XEvent = unsafe { zeroed() };
+    xevent.set_kind(99);
+    match FromNative::from_native(xevent) {
+      Event::Unknown(e) => {
+        assert_eq!(e.kind(), 99);
+        let back: ::ffi::XEvent = Event::Unknown(e).to_native();
+        assert_eq!(back.kind(), 99);
+      },
+      _ => panic!("expected Unknown"),
+    }
+  }
+}
@@ -0,0 +1,329 @@
+// Copyright (c) 2015, <daggerbot@gmail.com>
+// All rights reserved.
+
+use ::display::{
+  Atom,
+  Display,
+};
+use ::event::{
+  ClientMessageData,
+  ClientMessageEvent,
+  Event,
+  EventMask,
+};
+use ::property::{
+  PropertyData,
+  PropertyMode,
+};
+use ::window::Window;
+
+
+//
+// StateAction
+//
+
+
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub enum StateAction {
+  Remove,
+  Add,
+  Toggle,
+}
+
+impl StateAction {
+  fn to_u32 (&self) -> u32 {
+    match *self {
+      StateAction::Remove => 0,
+      StateAction::Add => 1,
+      StateAction::Toggle => 2,
+    }
+  }
+}
+
+
+//
+// WindowType
+//
+
+
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub enum WindowType {
+  Desktop,
+  Dock,
+  Toolbar,
+  Menu,
+  Utility,
+  Splash,
+  Dialog,
+  DropdownMenu,
+  PopupMenu,
+  Tooltip,
+  Notification,
+  Combo,
+  Dnd,
+  Normal,
+}
+
+impl WindowType {
+  fn all () -> [WindowType; 14] {
+    [WindowType::Desktop, WindowType::Dock, WindowType::Toolbar, WindowType::Menu, WindowType::Utility,
+     WindowType::Splash, WindowType::Dialog, WindowType::DropdownMenu, WindowType::PopupMenu, WindowType::Tooltip,
+     WindowType::Notification, WindowType::Combo, WindowType::Dnd, WindowType::Normal]
+  }
+
+  fn atom_name (&self) -> &'static str {
+    match *self {
+      WindowType::Desktop => "_NET_WM_WINDOW_TYPE_DESKTOP",
+      WindowType::Dock => "_NET_WM_WINDOW_TYPE_DOCK",
+      WindowType::Toolbar => "_NET_WM_WINDOW_TYPE_TOOLBAR",
+      WindowType::Menu => "_NET_WM_WINDOW_TYPE_MENU",
+      WindowType::Utility => "_NET_WM_WINDOW_TYPE_UTILITY",
+      WindowType::Splash => "_NET_WM_WINDOW_TYPE_SPLASH",
+      WindowType::Dialog => "_NET_WM_WINDOW_TYPE_DIALOG",
+      WindowType::DropdownMenu => "_NET_WM_WINDOW_TYPE_DROPDOWN_MENU",
+      WindowType::PopupMenu => "_NET_WM_WINDOW_TYPE_POPUP_MENU",
+      WindowType::Tooltip => "_NET_WM_WINDOW_TYPE_TOOLTIP",
+      WindowType::Notification => "_NET_WM_WINDOW_TYPE_NOTIFICATION",
+      WindowType::Combo => "_NET_WM_WINDOW_TYPE_COMBO",
+      WindowType::Dnd => "_NET_WM_WINDOW_TYPE_DND",
+      WindowType::Normal => "_NET_WM_WINDOW_TYPE_NORMAL",
+    }
+  }
+}
+
+
+//
+// WmState
+//
+
+
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub enum WmState {
+  Above,
+  Fullscreen,
+  MaximizedHorz,
+  MaximizedVert,
+  SkipTaskbar,
+}
+
+impl WmState {
+  fn all () -> [WmState; 5] {
+    [WmState::Above, WmState::Fullscreen, WmState::MaximizedHorz, WmState::MaximizedVert, WmState::SkipTaskbar]
+  }
+
+  fn atom_name (&self) -> &'static str {
+    match *self {
+      WmState::Above => "_NET_WM_STATE_ABOVE",
+      WmState::Fullscreen => "_NET_WM_STATE_FULLSCREEN",
+      WmState::MaximizedHorz => "_NET_WM_STATE_MAXIMIZED_HORZ",
+      WmState::MaximizedVert => "_NET_WM_STATE_MAXIMIZED_VERT",
+      WmState::SkipTaskbar => "_NET_WM_STATE_SKIP_TASKBAR",
+    }
+  }
+}
+
+
+//
+// public functions
+//
+
+
+/** Asks the window manager to add, remove or toggle states on a mapped window. States are sent in pairs, as the
+ * spec allows two per message, so maximizing both ways is a single request. */
+pub fn change_wm_state (display: &mut Display, window: Window, action: StateAction, states: &[WmState]) -> bool {
+  let root = match root_of(display, window) {Some(r) => r, None => return false};
+  let message_type = intern(display, "_NET_WM_STATE");
+  for pair in states.chunks(2) {
+    let first = intern(display, pair[0].atom_name());
+    let second = if pair.len() > 1 {intern(display, pair[1].atom_name()).0} else {0};
+    // source indication 1 marks the request as coming from a normal application
+    let data = ClientMessageData::Long([action.to_u32(), first.0, second, 1, 0]);
+    if !send_root_message(display, root, window, message_type, data) {
+      return false;
+    }
+  }
+  return true;
+}
+
+pub fn get_wm_icon_name (display: &mut Display, window: Window) -> Option<String> {
+  get_utf8_property(display, window, "_NET_WM_ICON_NAME")
+}
+
+pub fn get_wm_name (display: &mut Display, window: Window) -> Option<String> {
+  get_utf8_property(display, window, "_NET_WM_NAME")
+}
+
+pub fn get_wm_pid (display: &mut Display, window: Window) -> Option<u32> {
+  get_cardinal_property(display, window, "_NET_WM_PID")
+}
+
+/** Returns the states currently set on the window. States this module does not model are left out. */
+pub fn get_wm_state (display: &mut Display, window: Window) -> Vec<WmState> {
+  let atoms = get_atom_list_property(display, window, "_NET_WM_STATE");
+  let mut state_vec = Vec::new();
+  for state in WmState::all().iter() {
+    if let Some(atom) = display.intern_atom(state.atom_name(), true) {
+      if atoms.contains(&atom) {
+        state_vec.push(*state);
+      }
+    }
+  }
+  return state_vec;
+}
+
+pub fn get_wm_window_opacity (display: &mut Display, window: Window) -> Option<u32> {
+  get_cardinal_property(display, window, "_NET_WM_WINDOW_OPACITY")
+}
+
+/** Returns the window types set on the window, in order of preference. */
+pub fn get_wm_window_type (display: &mut Display, window: Window) -> Vec<WindowType> {
+  let atoms = get_atom_list_property(display, window, "_NET_WM_WINDOW_TYPE");
+  let mut type_vec = Vec::new();
+  for atom in atoms.iter() {
+    for window_type in WindowType::all().iter() {
+      if display.intern_atom(window_type.atom_name(), true) == Some(*atom) {
+        type_vec.push(*window_type);
+        break;
+      }
+    }
+  }
+  return type_vec;
+}
+
+/** Returns true if the window manager lists the named hint in `_NET_SUPPORTED` on the default root window. */
+pub fn is_supported (display: &mut Display, name: &str) -> bool {
+  match display.intern_atom(name, true) {
+    Some(atom) => supported(display).contains(&atom),
+    None => false,
+  }
+}
+
+pub fn set_wm_icon_name (display: &mut Display, window: Window, name: &str) {
+  set_utf8_property(display, window, "_NET_WM_ICON_NAME", name);
+}
+
+pub fn set_wm_name (display: &mut Display, window: Window, name: &str) {
+  set_utf8_property(display, window, "_NET_WM_NAME", name);
+}
+
+pub fn set_wm_pid (display: &mut Display, window: Window, pid: u32) {
+  set_cardinal_property(display, window, "_NET_WM_PID", pid);
+}
+
+/** Sets the initial states of a window that has not been mapped yet. Use `change_wm_state` once it is mapped. */
+pub fn set_wm_state (display: &mut Display, window: Window, states: &[WmState]) {
+  let mut atom_vec = Vec::with_capacity(states.len());
+  for state in states.iter() {
+    atom_vec.push(intern(display, state.atom_name()).0);
+  }
+  let property = intern(display, "_NET_WM_STATE");
+  let kind = intern(display, "ATOM");
+  display.change_property(window, property, kind, PropertyMode::Replace, &PropertyData::Long(atom_vec));
+}
+
+/** Sets the window opacity, where `0xffffffff` is fully opaque. */
+pub fn set_wm_window_opacity (display: &mut Display, window: Window, opacity: u32) {
+  set_cardinal_property(display, window, "_NET_WM_WINDOW_OPACITY", opacity);
+}
+
+pub fn set_wm_window_type (display: &mut Display, window: Window, types: &[WindowType]) {
+  let mut atom_vec = Vec::with_capacity(types.len());
+  for window_type in types.iter() {
+    atom_vec.push(intern(display, window_type.atom_name()).0);
+  }
+  let property = intern(display, "_NET_WM_WINDOW_TYPE");
+  let kind = intern(display, "ATOM");
+  display.change_property(window, property, kind, PropertyMode::Replace, &PropertyData::Long(atom_vec));
+}
+
+/** Returns the hints listed in `_NET_SUPPORTED` on the default root window. The list is empty if no EWMH-compliant
+ * window manager is running. */
+pub fn supported (display: &mut Display) -> Vec<Atom> {
+  let screen_num = display.default_screen();
+  let root = display.root_window(screen_num);
+  get_atom_list_property(display, root, "_NET_SUPPORTED")
+}
+
+
+//
+// private functions
+//
+
+
+fn get_atom_list_property (display: &mut Display, window: Window, name: &str) -> Vec<Atom> {
+  let property = match display.intern_atom(name, true) {Some(a) => a, None => return Vec::new()};
+  let kind = intern(display, "ATOM");
+  match display.get_property(window, property, Some(kind), false) {
+    Some(prop) => match prop.data {
+      PropertyData::Long(ref longs) => longs.iter().map(|long| Atom(*long)).collect(),
+      _ => Vec::new(),
+    },
+    None => Vec::new(),
+  }
+}
+
+fn get_cardinal_property (display: &mut Display, window: Window, name: &str) -> Option<u32> {
+  let property = match display.intern_atom(name, true) {Some(a) => a, None => return None};
+  let kind = intern(display, "CARDINAL");
+  match display.get_property(window, property, Some(kind), false) {
+    Some(prop) => match prop.data {
+      PropertyData::Long(ref longs) if !longs.is_empty() => Some(longs[0]),
+      _ => None,
+    },
+    None => None,
+  }
+}
+
+fn get_utf8_property (display: &mut Display, window: Window, name: &str) -> Option<String> {
+  let property = match display.intern_atom(name, true) {Some(a) => a, None => return None};
+  let kind = intern(display, "UTF8_STRING");
+  match display.get_property(window, property, Some(kind), false) {
+    Some(prop) => match prop.data {
+      PropertyData::Byte(ref bytes) => Some(String::from_utf8_lossy(bytes).into_owned()),
+      _ => None,
+    },
+    None => None,
+  }
+}
+
+fn intern (display: &mut Display, name: &str) -> Atom {
+  match display.intern_atom(name, false) {
+    Some(atom) => atom,
+    None => {
+      error!("XInternAtom failed for {}", name);
+      Atom(0)
+    },
+  }
+}
+
+fn root_of (display: &mut Display, window: Window) -> Option<Window> {
+  display.get_window_attributes(window).map(|attr| attr.root)
+}
+
+fn send_root_message (display: &mut Display, root: Window, window: Window, message_type: Atom,
+    data: ClientMessageData) -> bool {
+  let event = Event::ClientMessage(ClientMessageEvent {
+    serial: 0,
+    send_event: true,
+    window: window,
+    message_type: message_type,
+    data: data,
+  });
+  let mut event_mask = EventMask::empty();
+  event_mask.substructure_notify = true;
+  event_mask.substructure_redirect = true;
+  return display.send_event_to(root, false, event_mask, event);
+}
+
+fn set_cardinal_property (display: &mut Display, window: Window, name: &str, value: u32) {
+  let property = intern(display, name);
+  let kind = intern(display, "CARDINAL");
+  display.change_property(window, property, kind, PropertyMode::Replace, &PropertyData::Long(vec![value]));
+}
+
+fn set_utf8_property (display: &mut Display, window: Window, name: &str, value: &str) {
+  let property = intern(display, name);
+  let kind = intern(display, "UTF8_STRING");
+  display.change_property(window, property, kind, PropertyMode::Replace,
+      &PropertyData::Byte(value.as_bytes().to_vec()));
+}
@@ -24,22 +24,50 @@ use libc::{
 extern "C" {
   pub fn XAllocColor (display: *mut Display, colormap: Colormap, color: *mut XColor) -> Status;
   pub fn XAllPlanes () -> c_ulong;
+  pub fn XBitmapBitOrder (display: *mut Display) -> c_int;
+  pub fn XBitmapPad (display: *mut Display) -> c_int;
+  pub fn XBitmapUnit (display: *mut Display) -> c_int;
   pub fn XBlackPixel (display: *mut Display, screen_num: c_int) -> c_ulong;
+  pub fn XBlackPixelOfScreen (screen: *const Screen) -> c_ulong;
+  pub fn XCheckIfEvent (display: *mut Display, event: *mut XEvent, predicate: EventPredicate, arg: XPointer) -> Bool;
+  pub fn XCheckTypedEvent (display: *mut Display, event_type: c_int, event: *mut XEvent) -> Bool;
+  pub fn XCheckTypedWindowEvent (display: *mut Display, window: Window, event_type: c_int, event: *mut XEvent)
+      -> Bool;
+  pub fn XCheckWindowEvent (display: *mut Display, window: Window, event_mask: c_long, event: *mut XEvent) -> Bool;
+  pub fn XChangeProperty (display: *mut Display, window: Window, property: Atom, kind: Atom, format: c_int,
+      mode: c_int, data: *const c_uchar, nelements: c_int);
+  pub fn XCirculateSubwindows (display: *mut Display, window: Window, direction: c_int);
   pub fn XCloseDisplay (display: *mut Display);
+  pub fn XConfigureWindow (display: *mut Display, window: Window, value_mask: c_uint, values: *const XWindowChanges);
+  pub fn XConnectionNumber (display: *mut Display) -> c_int;
   pub fn XCreateColormap (display: *mut Display, window: Window, visual: *const Visual, alloc: c_int) -> Colormap;
   pub fn XCreateGC (display: *mut Display, drawable: Drawable, valuemask: c_ulong, values: *const XGCValues) -> GC;
+  pub fn XCreatePixmap (display: *mut Display, drawable: Drawable, width: c_uint, height: c_uint, depth: c_uint)
+      -> Pixmap;
   pub fn XCreateSimpleWindow (display: *mut Display, parent: Window, x: c_int, y: c_int, width: c_uint, height: c_uint,
       border_width: c_uint, border_pixel: c_ulong, background_pixel: c_ulong) -> Window;
   pub fn XCreateWindow (display: *mut Display, parent: Window, x: c_int, y: c_int, width: c_uint, height: c_uint,
       border_width: c_uint, depth: c_int, class: c_uint, visual: *const Visual, valuemask: c_ulong,
       attributes: *const XSetWindowAttributes) -> Window;
   pub fn XDefaultColormap (display: *mut Display, screen_num: c_int) -> Colormap;
+  pub fn XDefaultColormapOfScreen (screen: *const Screen) -> Colormap;
+  pub fn XDefaultDepthOfScreen (screen: *const Screen) -> c_int;
+  pub fn XDefaultGCOfScreen (screen: *const Screen) -> GC;
   pub fn XDefaultScreen (display: *mut Display) -> c_int;
+  pub fn XDefaultScreenOfDisplay (display: *mut Display) -> *const Screen;
   pub fn XDefaultVisual (display: *mut Display, screen_num: c_int) -> *const Visual;
+  pub fn XDefaultVisualOfScreen (screen: *const Screen) -> *const Visual;
+  pub fn XDisplayMotionBufferSize (display: *mut Display) -> c_ulong;
+  pub fn XDisplayOfScreen (screen: *const Screen) -> *mut Display;
+  pub fn XDisplayString (display: *mut Display) -> *mut c_char;
+  pub fn XDeleteProperty (display: *mut Display, window: Window, property: Atom);
   pub fn XDestroyWindow (display: *mut Display, window: Window);
+  pub fn XDoesBackingStore (screen: *const Screen) -> c_int;
+  pub fn XDoesSaveUnders (screen: *const Screen) -> Bool;
   pub fn XDrawLine (display: *mut Display, drawable: Drawable, gc: GC, x0: c_int, y0: c_int, x1: c_int, y1: c_int);
   pub fn XDrawRectangle (display: *mut Display, drawable: Drawable, gc: GC, x: c_int, y: c_int, width: c_uint,
       height: c_uint);
+  pub fn XExtendedMaxRequestSize (display: *mut Display) -> c_long;
   pub fn XFetchName (display: *mut Display, window: Window, name: *mut *mut c_char) -> Status;
   pub fn XFillRectangle (display: *mut Display, drawable: Drawable, gc: GC, x: c_int, y: c_int, width: c_uint,
       height: c_uint);
@@ -47,35 +75,110 @@ extern "C" {
   pub fn XFree (mem: *mut c_void);
   pub fn XFreeColormap (display: *mut Display, colormap: Colormap);
   pub fn XFreeGC (display: *mut Display, gc: GC);
+  pub fn XFreePixmap (display: *mut Display, pixmap: Pixmap);
+  pub fn XFreeStringList (list: *mut *mut c_char);
+  pub fn XGContextFromGC (gc: GC) -> GContext;
+  pub fn XGetClassHint (display: *mut Display, window: Window, class_hints_return: *mut XClassHint) -> Status;
+  pub fn XGetCommand (display: *mut Display, window: Window, argv_return: *mut *mut *mut c_char,
+      argc_return: *mut c_int) -> Status;
   pub fn XGetErrorText (display: *mut Display, code: c_int, buffer: *mut c_char, len: c_int);
   pub fn XGetGeometry (display: *mut Display, drawable: Drawable, root_return: *mut Window, x_result: *mut c_int,
       y_return: *mut c_int, width_return: *mut c_uint, height_return: *mut c_uint, border_width_return: *mut c_uint,
       depth_return: *mut c_uint) -> Status;
+  pub fn XGetIconName (display: *mut Display, window: Window, icon_name_return: *mut *mut c_char) -> Status;
+  pub fn XGetTextProperty (display: *mut Display, window: Window, text_prop_return: *mut XTextProperty, property: Atom)
+      -> Status;
+  pub fn XGetTransientForHint (display: *mut Display, window: Window, prop_window_return: *mut Window) -> Status;
   pub fn XGetVisualInfo (display: *mut Display, vinfo_mask: c_long, vinfo_template: *const XVisualInfo,
       count: *mut c_int) -> *mut XVisualInfo;
+  pub fn XGetWMHints (display: *mut Display, window: Window) -> *mut XWMHints;
+  pub fn XGetWMNormalHints (display: *mut Display, window: Window, hints_return: *mut XSizeHints,
+      supplied_return: *mut c_long) -> Status;
+  pub fn XGetWMName (display: *mut Display, window: Window, text_prop_return: *mut XTextProperty) -> Status;
   pub fn XGetWindowAttributes (display: *mut Display, window: Window, attr: *mut XWindowAttributes) -> Status;
+  pub fn XGetWindowProperty (display: *mut Display, window: Window, property: Atom, long_offset: c_long,
+      long_length: c_long, delete: Bool, req_type: Atom, actual_type_return: *mut Atom,
+      actual_format_return: *mut c_int, nitems_return: *mut c_ulong, bytes_after_return: *mut c_ulong,
+      prop_return: *mut *mut c_uchar) -> c_int;
+  pub fn XHeightMMOfScreen (screen: *const Screen) -> c_int;
+  pub fn XHeightOfScreen (screen: *const Screen) -> c_int;
+  pub fn XIfEvent (display: *mut Display, event: *mut XEvent, predicate: EventPredicate, arg: XPointer);
+  pub fn XImageByteOrder (display: *mut Display) -> c_int;
+  pub fn XInitThreads () -> Status;
   pub fn XInternAtom (display: *mut Display, name: *const c_char, only_if_exists: Bool) -> Atom;
+  pub fn XKeysymToString (keysym: KeySym) -> *mut c_char;
+  pub fn XListDepths (display: *mut Display, screen_number: c_int, count: *mut c_int) -> *mut c_int;
+  pub fn XListProperties (display: *mut Display, window: Window, num_prop_return: *mut c_int) -> *mut Atom;
+  pub fn XListPixmapFormats (display: *mut Display, count: *mut c_int) -> *mut XPixmapFormatValues;
+  pub fn XLockDisplay (display: *mut Display);
+  pub fn XLookupKeysym (key_event: *mut XKeyEvent, index: c_int) -> KeySym;
+  pub fn XLookupString (event: *mut XKeyEvent, buffer: *mut c_char, bytes_buffer: c_int, keysym: *mut KeySym,
+      status: *mut XComposeStatus) -> c_int;
+  pub fn XLowerWindow (display: *mut Display, window: Window);
   pub fn XMapWindow (display: *mut Display, window: Window);
+  pub fn XMaxRequestSize (display: *mut Display) -> c_long;
+  pub fn XMaxCmapsOfScreen (screen: *const Screen) -> c_int;
+  pub fn XMinCmapsOfScreen (screen: *const Screen) -> c_int;
   pub fn XMoveWindow (display: *mut Display, window: Window, x: c_int, y: c_int);
   pub fn XNextEvent (display: *mut Display, event: *mut XEvent);
+  pub fn XNextRequest (display: *mut Display) -> c_ulong;
   pub fn XOpenDisplay (name: *const c_char) -> *mut Display;
+  pub fn XPeekEvent (display: *mut Display, event: *mut XEvent);
   pub fn XPending (display: *mut Display) -> c_int;
+  pub fn XProtocolRevision (display: *mut Display) -> c_int;
+  pub fn XProtocolVersion (display: *mut Display) -> c_int;
+  pub fn XPutBackEvent (display: *mut Display, event: *mut XEvent);
+  pub fn XQueryTree (display: *mut Display, window: Window, root_return: *mut Window, parent_return: *mut Window,
+      children_return: *mut *mut Window, nchildren_return: *mut c_uint) -> Status;
+  pub fn XRaiseWindow (display: *mut Display, window: Window);
   pub fn XResizeWindow (display: *mut Display, window: Window, width: c_uint, height: c_uint);
+  pub fn XRestackWindows (display: *mut Display, windows: *mut Window, nwindows: c_int);
+  pub fn XRotateWindowProperties (display: *mut Display, window: Window, properties: *mut Atom, num_prop: c_int,
+      npositions: c_int);
   pub fn XRootWindow (display: *mut Display, screen_num: c_int) -> Window;
+  pub fn XRootWindowOfScreen (screen: *const Screen) -> Window;
   pub fn XScreenCount (display: *mut Display) -> c_int;
+  pub fn XScreenNumberOfScreen (screen: *const Screen) -> c_int;
+  pub fn XScreenOfDisplay (display: *mut Display, screen_number: c_int) -> *const Screen;
+  pub fn XSelectInput (display: *mut Display, window: Window, event_mask: c_long);
   pub fn XSendEvent (display: *mut Display, window: Window, propagate: Bool, event_mask: c_long, event: *const XEvent)
       -> Bool;
+  pub fn XServerVendor (display: *mut Display) -> *mut c_char;
+  pub fn XSetAfterFunction (display: *mut Display, procedure: AfterFunction) -> AfterFunction;
+  pub fn XSetClassHint (display: *mut Display, window: Window, class_hints: *const XClassHint);
   pub fn XSetClipRectangles (display: *mut Display, gc: GC, clip_x_origin: c_int, clip_y_origin: c_int,
       rectangles: *const XRectangle, num_rectangles: c_int, ordering: c_int);
+  pub fn XSetCommand (display: *mut Display, window: Window, argv: *mut *mut c_char, argc: c_int);
   pub fn XSetErrorHandler (handler: ErrorHandler) -> ErrorHandler;
   pub fn XSetForeground (display: *mut Display, gc: GC, pixel: c_ulong);
+  pub fn XSetIOErrorExitHandler (display: *mut Display, handler: IoErrorExitHandler, user_data: *mut c_void);
   pub fn XSetIOErrorHandler (handler: IoErrorHandler) -> IoErrorHandler;
+  pub fn XSetIconName (display: *mut Display, window: Window, icon_name: *const c_char);
+  pub fn XSetTextProperty (display: *mut Display, window: Window, text_prop: *const XTextProperty, property: Atom);
+  pub fn XSetTransientForHint (display: *mut Display, window: Window, prop_window: Window);
+  pub fn XSetWMHints (display: *mut Display, window: Window, wm_hints: *const XWMHints);
+  pub fn XSetWMName (display: *mut Display, window: Window, text_prop: *const XTextProperty);
   pub fn XSetWMNormalHints (display: *mut Display, window: Window, hints: *const XSizeHints);
   pub fn XSetWMProtocols (display: *mut Display, window: Window, protocols: *const Atom, count: c_int) -> Status;
+  pub fn XSetWindowBorderWidth (display: *mut Display, window: Window, width: c_uint);
   pub fn XStoreName (display: *mut Display, window: Window, name: *const c_char);
+  pub fn XStringToKeysym (name: *const c_char) -> KeySym;
+  pub fn XSync (display: *mut Display, discard: Bool);
+  pub fn XSynchronize (display: *mut Display, onoff: Bool) -> AfterFunction;
+  pub fn XTranslateCoordinates (display: *mut Display, src_w: Window, dest_w: Window, src_x: c_int, src_y: c_int,
+      dest_x_return: *mut c_int, dest_y_return: *mut c_int, child_return: *mut Window) -> Bool;
+  pub fn XUnlockDisplay (display: *mut Display);
   pub fn XUnmapWindow (display: *mut Display, window: Window);
+  pub fn XVendorRelease (display: *mut Display) -> c_int;
   pub fn XVisualIDFromVisual (visual: *const Visual) -> VisualID;
   pub fn XWhitePixel (display: *mut Display, screen_num: c_int) -> c_ulong;
+  pub fn XWhitePixelOfScreen (screen: *const Screen) -> c_ulong;
+  pub fn XWidthMMOfScreen (screen: *const Screen) -> c_int;
+  pub fn XWidthOfScreen (screen: *const Screen) -> c_int;
+  pub fn Xutf8TextListToTextProperty (display: *mut Display, list: *mut *mut c_char, count: c_int,
+      style: XICCEncodingStyle, text_prop_return: *mut XTextProperty) -> c_int;
+  pub fn Xutf8TextPropertyToTextList (display: *mut Display, text_prop: *const XTextProperty,
+      list_return: *mut *mut *mut c_char, count_return: *mut c_int) -> c_int;
 }
 
 
@@ -92,15 +195,23 @@ pub type Cursor = XID;
 pub type Drawable = XID;
 pub type Font = XID;
 pub type GC = XID;
+pub type GContext = XID;
 pub type GLXDrawable = XID;
+pub type KeySym = XID;
 pub type Pixmap = XID;
 pub type Status = Bool;
+pub type Time = c_ulong;
 pub type VisualID = XID;
 pub type Window = XID;
+pub type XICCEncodingStyle = c_int;
 pub type XID = c_ulong;
+pub type XPointer = *mut c_char;
 
 // function pointer types
+pub type AfterFunction = Option<unsafe extern "C" fn (*mut Display) -> c_int>;
+pub type EventPredicate = unsafe extern "C" fn (*mut Display, *mut XEvent, XPointer) -> Bool;
 pub type ErrorHandler = unsafe extern "C" fn (*mut Display, *const XErrorEvent) -> c_int;
+pub type IoErrorExitHandler = unsafe extern "C" fn (*mut Display, *mut c_void);
 pub type IoErrorHandler = unsafe extern "C" fn (*mut Display) -> c_int;
 
 // opaque structs
@@ -133,6 +244,28 @@ pub struct XAnyEvent {
   pub window: Window,
 }
 
+// XButtonEvent
+#[allow(raw_pointer_derive)]
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct XButtonEvent {
+  pub kind: c_int,
+  pub serial: c_ulong,
+  pub send_event: Bool,
+  pub display: *mut Display,
+  pub window: Window,
+  pub root: Window,
+  pub subwindow: Window,
+  pub time: Time,
+  pub x: c_int,
+  pub y: c_int,
+  pub x_root: c_int,
+  pub y_root: c_int,
+  pub state: c_uint,
+  pub button: c_uint,
+  pub same_screen: Bool,
+}
+
 // XClientMessageEvent
 #[allow(raw_pointer_derive)]
 #[derive(Clone, Copy)]
@@ -186,6 +319,15 @@ impl XClientMessageEvent {
   }
 }
 
+// XClassHint
+#[allow(raw_pointer_derive)]
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct XClassHint {
+  pub res_name: *mut c_char,
+  pub res_class: *mut c_char,
+}
+
 // XColor
 #[derive(Clone, Copy)]
 #[repr(C)]
@@ -198,6 +340,15 @@ pub struct XColor {
   pub pad: c_char,
 }
 
+// XComposeStatus
+#[allow(raw_pointer_derive)]
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct XComposeStatus {
+  pub compose_ptr: *mut c_char,
+  pub chars_matched: c_int,
+}
+
 // XConfigureEvent
 #[allow(raw_pointer_derive)]
 #[derive(Clone, Copy)]
@@ -218,6 +369,30 @@ pub struct XConfigureEvent {
   pub override_redirect: Bool,
 }
 
+// XCrossingEvent
+#[allow(raw_pointer_derive)]
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct XCrossingEvent {
+  pub kind: c_int,
+  pub serial: c_ulong,
+  pub send_event: Bool,
+  pub display: *mut Display,
+  pub window: Window,
+  pub root: Window,
+  pub subwindow: Window,
+  pub time: Time,
+  pub x: c_int,
+  pub y: c_int,
+  pub x_root: c_int,
+  pub y_root: c_int,
+  pub mode: c_int,
+  pub detail: c_int,
+  pub same_screen: Bool,
+  pub focus: Bool,
+  pub state: c_uint,
+}
+
 // XDestroyWindowEvent
 #[allow(raw_pointer_derive)]
 #[derive(Clone, Copy)]
@@ -258,6 +433,12 @@ impl XEvent {
       *(&self.pad[0] as *const c_long as *const c_int)
     }
   }
+
+  pub fn set_kind (&mut self, kind: c_int) {
+    unsafe {
+      *(&mut self.pad[0] as *mut c_long as *mut c_int) = kind;
+    }
+  }
 }
 
 // XExposeEvent
@@ -277,6 +458,20 @@ pub struct XExposeEvent {
   pub count: c_int,
 }
 
+// XFocusChangeEvent
+#[allow(raw_pointer_derive)]
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct XFocusChangeEvent {
+  pub kind: c_int,
+  pub serial: c_ulong,
+  pub send_event: Bool,
+  pub display: *mut Display,
+  pub window: Window,
+  pub mode: c_int,
+  pub detail: c_int,
+}
+
 // XGCValues
 #[derive(Clone, Copy)]
 #[repr(C)]
@@ -306,6 +501,28 @@ pub struct XGCValues {
   pub dashes: c_char,
 }
 
+// XKeyEvent
+#[allow(raw_pointer_derive)]
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct XKeyEvent {
+  pub kind: c_int,
+  pub serial: c_ulong,
+  pub send_event: Bool,
+  pub display: *mut Display,
+  pub window: Window,
+  pub root: Window,
+  pub subwindow: Window,
+  pub time: Time,
+  pub x: c_int,
+  pub y: c_int,
+  pub x_root: c_int,
+  pub y_root: c_int,
+  pub state: c_uint,
+  pub keycode: c_uint,
+  pub same_screen: Bool,
+}
+
 // XMapEvent
 #[allow(raw_pointer_derive)]
 #[derive(Clone, Copy)]
@@ -320,6 +537,52 @@ pub struct XMapEvent {
   pub override_redirect: Bool,
 }
 
+// XMotionEvent
+#[allow(raw_pointer_derive)]
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct XMotionEvent {
+  pub kind: c_int,
+  pub serial: c_ulong,
+  pub send_event: Bool,
+  pub display: *mut Display,
+  pub window: Window,
+  pub root: Window,
+  pub subwindow: Window,
+  pub time: Time,
+  pub x: c_int,
+  pub y: c_int,
+  pub x_root: c_int,
+  pub y_root: c_int,
+  pub state: c_uint,
+  pub is_hint: c_char,
+  pub same_screen: Bool,
+}
+
+// XPixmapFormatValues
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct XPixmapFormatValues {
+  pub depth: c_int,
+  pub bits_per_pixel: c_int,
+  pub scanline_pad: c_int,
+}
+
+// XPropertyEvent
+#[allow(raw_pointer_derive)]
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct XPropertyEvent {
+  pub kind: c_int,
+  pub serial: c_ulong,
+  pub send_event: Bool,
+  pub display: *mut Display,
+  pub window: Window,
+  pub atom: Atom,
+  pub time: Time,
+  pub state: c_int,
+}
+
 // XRectangle
 #[derive(Clone, Copy)]
 #[repr(C)]
@@ -373,6 +636,17 @@ pub struct XSizeHints {
   pub win_gravity: c_int,
 }
 
+// XTextProperty
+#[allow(raw_pointer_derive)]
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct XTextProperty {
+  pub value: *mut c_uchar,
+  pub encoding: Atom,
+  pub format: c_int,
+  pub nitems: c_ulong,
+}
+
 // XUnmapEvent
 #[allow(raw_pointer_derive)]
 #[derive(Clone, Copy)]
@@ -404,6 +678,21 @@ pub struct XVisualInfo {
   pub bits_per_rgb: c_int,
 }
 
+// XWMHints
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct XWMHints {
+  pub flags: c_long,
+  pub input: Bool,
+  pub initial_state: c_int,
+  pub icon_pixmap: Pixmap,
+  pub icon_window: Window,
+  pub icon_x: c_int,
+  pub icon_y: c_int,
+  pub icon_mask: Pixmap,
+  pub window_group: XID,
+}
+
 // XWindowAttributes
 #[allow(raw_pointer_derive)]
 #[derive(Clone, Copy)]
@@ -434,6 +723,19 @@ pub struct XWindowAttributes {
   pub screen: *const Screen,
 }
 
+// XWindowChanges
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct XWindowChanges {
+  pub x: c_int,
+  pub y: c_int,
+  pub width: c_int,
+  pub height: c_int,
+  pub border_width: c_int,
+  pub sibling: Window,
+  pub stack_mode: c_int,
+}
+
 
 //
 // constants
@@ -478,3 +780,19 @@ pub const SelectionNotify: c_int = 31;
 pub const ColormapNotify: c_int = 32;
 pub const ClientMessage: c_int = 33;
 pub const MappingNotify: c_int = 34;
+
+// property mode
+pub const PropModeReplace: c_int = 0;
+pub const PropModePrepend: c_int = 1;
+pub const PropModeAppend: c_int = 2;
+
+// text encoding style
+pub const XStringStyle: XICCEncodingStyle = 0;
+pub const XCompoundTextStyle: XICCEncodingStyle = 1;
+pub const XTextStyle: XICCEncodingStyle = 2;
+pub const XStdICCTextStyle: XICCEncodingStyle = 3;
+pub const XUTF8StringStyle: XICCEncodingStyle = 4;
+
+// reserved resource and constant definitions
+pub const AnyPropertyType: Atom = 0;
+pub const Success: c_int = 0;
@@ -4,4 +4,5 @@
 use ::display::Xid;
 
 /** Font resource identifier type. */
-pub type Font = Xid;
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Font(pub Xid);
@@ -8,16 +8,24 @@ use libc::{
   c_ushort,
 };
 
+use ::display::{
+  Connection,
+  Xid,
+};
 use ::font::Font;
-use ::display::Xid;
 use ::internal::{
   FieldMask,
   ToNative,
 };
+use ::owned::{
+  Owned,
+  Resource,
+};
 use ::pixmap::Pixmap;
 
 /** Graphics context resource identifier type. Renamed from `Gc` to `Gcid` to avoid confusion with `std::gc::Gc`. */
-pub type Gcid = Xid;
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Gcid(pub Xid);
 
 
 //
@@ -262,16 +270,16 @@ impl ToNative<::ffi::XGCValues> for GcValues {
       fill_style: if let Some(s) = self.fill_style {s.to_native()} else {0},
       fill_rule: if let Some(r) = self.fill_rule {r.to_native()} else {0},
       arc_mode: if let Some(m) = self.arc_mode {m.to_native()} else {0},
-      tile: if let Some(p) = self.tile {p as c_ulong} else {0},
-      stipple: if let Some(p) = self.stipple {p as c_ulong} else {0},
+      tile: if let Some(p) = self.tile {p.0 as c_ulong} else {0},
+      stipple: if let Some(p) = self.stipple {p.0 as c_ulong} else {0},
       ts_x_origin: if let Some(n) = self.ts_x_origin {n as c_int} else {0},
       ts_y_origin: if let Some(n) = self.ts_y_origin {n as c_int} else {0},
-      font: if let Some(f) = self.font {f as c_ulong} else {0},
+      font: if let Some(f) = self.font {f.0 as c_ulong} else {0},
       subwindow_mode: if let Some(m) = self.subwindow_mode {m.to_native()} else {0},
       graphics_exposures: if let Some(b) = self.graphics_exposures {if b {1} else {0}} else {0},
       clip_x_origin: if let Some(n) = self.clip_x_origin {n as c_int} else {0},
       clip_y_origin: if let Some(n) = self.clip_y_origin {n as c_int} else {0},
-      clip_mask: if let Some(p) = self.clip_mask {p as c_ulong} else {0},
+      clip_mask: if let Some(p) = self.clip_mask {p.0 as c_ulong} else {0},
       dash_offset: if let Some(n) = self.dash_offset {n as c_int} else {0},
       dashes: 0, //TODO
     }
@@ -325,6 +333,23 @@ impl ToNative<c_int> for LineStyle {
 }
 
 
+//
+// OwnedGc
+//
+
+
+/** A graphics context that is freed when dropped. */
+pub type OwnedGc = Owned<Gcid>;
+
+impl Resource for Gcid {
+  fn free (self, connection: &Connection) {
+    unsafe {
+      ::ffi::XFreeGC(connection.to_native(), self.0 as c_ulong);
+    }
+  }
+}
+
+
 //
 // Rectangle
 //
@@ -30,3 +30,27 @@ pub trait FromNative<T> {
 pub trait ToNative<T> {
   fn to_native (&self) -> T;
 }
+
+
+//
+// functions
+//
+
+
+/** Decodes ISO 8859-1 text, as returned by XLookupString and the STRING property type. */
+pub fn latin1_to_string (bytes: &[u8]) -> String {
+  let mut string = String::with_capacity(bytes.len());
+  for byte in bytes.iter() {
+    string.push(*byte as char);
+  }
+  return string;
+}
+
+/** Encodes text as ISO 8859-1 for STRING properties. Characters outside Latin-1 are replaced with '?'. */
+pub fn string_to_latin1 (string: &str) -> Vec<u8> {
+  let mut bytes = Vec::with_capacity(string.len());
+  for c in string.chars() {
+    bytes.push(if (c as u32) < 0x100 {c as u8} else {b'?'});
+  }
+  return bytes;
+}
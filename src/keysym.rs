@@ -0,0 +1,41 @@
+// Copyright (c) 2015, <daggerbot@gmail.com>
+// All rights reserved.
+
+use std::ffi::CString;
+
+use libc::c_ulong;
+
+/** Key symbol type. */
+pub type Keysym = u32;
+
+
+//
+// public functions
+//
+
+
+pub fn keysym_to_string (keysym: Keysym) -> Option<String> {
+  unsafe {
+    // the returned string is static and must not be freed
+    let name_ptr = ::ffi::XKeysymToString(keysym as c_ulong);
+    if name_ptr.is_null() {
+      return None;
+    }
+    let name_byte_ptr = name_ptr as *const u8;
+    let name_len = ::libc::strlen(name_ptr as *const ::libc::c_char) as usize;
+    let name_slice = ::std::slice::from_raw_buf(&name_byte_ptr, name_len);
+    return Some(String::from_utf8_lossy(name_slice).into_owned());
+  }
+}
+
+pub fn string_to_keysym (name: &str) -> Option<Keysym> {
+  unsafe {
+    let name_c_str = CString::from_slice(name.as_bytes());
+    let keysym = ::ffi::XStringToKeysym(name_c_str.as_ptr());
+    if keysym == 0 {
+      return None;
+    } else {
+      return Some(keysym as Keysym);
+    }
+  }
+}
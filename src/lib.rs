@@ -6,19 +6,30 @@
 
 #![allow(unstable)] // remove this when Rust is in beta
 
+#[cfg(feature="async")]
+extern crate futures_core;
 extern crate libc;
 #[macro_use]
 extern crate log;
+#[cfg(feature="async")]
+extern crate tokio;
 
 pub mod colormap;
 pub mod cursor;
 pub mod display;
 pub mod drawable;
+pub mod error;
 pub mod event;
+pub mod ewmh;
 pub mod font;
 pub mod gc;
+pub mod keysym;
+pub mod owned;
 pub mod pixmap;
+pub mod property;
 pub mod screen;
+#[cfg(feature="async")]
+pub mod stream;
 pub mod visual;
 pub mod window;
 
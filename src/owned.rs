@@ -0,0 +1,73 @@
+// Copyright (c) 2015, <daggerbot@gmail.com>
+// All rights reserved.
+
+use std::mem::forget;
+use std::ptr::read;
+use std::sync::Arc;
+
+use ::display::{
+  Connection,
+  Display,
+  Xid,
+};
+use ::drawable::Drawable;
+
+
+//
+// Owned
+//
+
+
+/** A server resource that is freed when dropped. Keeps the connection open until then. */
+pub struct Owned<T: Resource> {
+  connection: Arc<Connection>,
+  resource: T,
+}
+
+impl<T: Resource> Owned<T> {
+  /** Takes ownership of `resource`, which must have been created on `display`. */
+  pub fn from_raw (display: &Display, resource: T) -> Owned<T> {
+    Owned {
+      connection: display.connection(),
+      resource: resource,
+    }
+  }
+
+  pub fn id (&self) -> T {
+    self.resource
+  }
+
+  /** Releases ownership without freeing the resource. */
+  pub fn into_raw (self) -> T {
+    let resource = self.resource;
+    unsafe {
+      drop(read(&self.connection));
+      forget(self);
+    }
+    return resource;
+  }
+}
+
+// Only by reference, so that passing a handle to a drawing call doesn't move it in and free it on return.
+impl<'a, T: Resource + Drawable> Drawable for &'a Owned<T> {
+  fn drawable_id (&self) -> Xid {
+    self.resource.drawable_id()
+  }
+}
+
+impl<T: Resource> Drop for Owned<T> {
+  fn drop (&mut self) {
+    self.resource.free(&self.connection);
+  }
+}
+
+
+//
+// Resource
+//
+
+
+/** Resource identifiers that `Owned` knows how to free. */
+pub trait Resource: Copy {
+  fn free (self, connection: &Connection);
+}
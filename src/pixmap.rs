@@ -1,7 +1,68 @@
 // Copyright (c) 2015, <daggerbot@gmail.com>
 // All rights reserved.
 
-use ::display::Xid;
+use libc::c_ulong;
+
+use ::display::{
+  Connection,
+  Xid,
+};
+use ::drawable::Drawable;
+use ::internal::{
+  FromNative,
+  ToNative,
+};
+use ::owned::{
+  Owned,
+  Resource,
+};
 
 /** Pixmap identifier type. */
-pub type Pixmap = Xid;
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Pixmap(pub Xid);
+
+impl Drawable for Pixmap {
+  fn drawable_id (&self) -> Xid {
+    self.0
+  }
+}
+
+
+//
+// OwnedPixmap
+//
+
+
+/** A pixmap that is freed when dropped. */
+pub type OwnedPixmap = Owned<Pixmap>;
+
+impl Resource for Pixmap {
+  fn free (self, connection: &Connection) {
+    unsafe {
+      ::ffi::XFreePixmap(connection.to_native(), self.0 as c_ulong);
+    }
+  }
+}
+
+
+//
+// PixmapFormat
+//
+
+
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub struct PixmapFormat {
+  pub depth: i32,
+  pub bits_per_pixel: i32,
+  pub scanline_pad: i32,
+}
+
+impl FromNative<::ffi::XPixmapFormatValues> for PixmapFormat {
+  fn from_native (xformat: ::ffi::XPixmapFormatValues) -> PixmapFormat {
+    PixmapFormat {
+      depth: xformat.depth as i32,
+      bits_per_pixel: xformat.bits_per_pixel as i32,
+      scanline_pad: xformat.scanline_pad as i32,
+    }
+  }
+}
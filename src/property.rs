@@ -0,0 +1,148 @@
+// Copyright (c) 2015, <daggerbot@gmail.com>
+// All rights reserved.
+
+use libc::c_int;
+
+use ::display::Atom;
+use ::event::{
+  Event,
+  PropertyEvent,
+};
+use ::internal::ToNative;
+use ::window::Window;
+
+
+//
+// Property
+//
+
+
+#[derive(Clone, Eq, Hash, PartialEq)]
+pub struct Property {
+  pub kind: Atom,
+  pub data: PropertyData,
+}
+
+
+//
+// PropertyData
+//
+
+
+/** Property contents, tagged by the property's format (8, 16 or 32 bits per item). */
+#[derive(Clone, Eq, Hash, PartialEq)]
+pub enum PropertyData {
+  Byte(Vec<u8>),
+  Short(Vec<u16>),
+  Long(Vec<u32>),
+}
+
+impl PropertyData {
+  pub fn format (&self) -> i32 {
+    match *self {
+      PropertyData::Byte(_) => 8,
+      PropertyData::Short(_) => 16,
+      PropertyData::Long(_) => 32,
+    }
+  }
+
+  pub fn len (&self) -> usize {
+    match *self {
+      PropertyData::Byte(ref data) => data.len(),
+      PropertyData::Short(ref data) => data.len(),
+      PropertyData::Long(ref data) => data.len(),
+    }
+  }
+}
+
+
+//
+// PropertyMode
+//
+
+
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub enum PropertyMode {
+  Replace,
+  Prepend,
+  Append,
+}
+
+impl ToNative<c_int> for PropertyMode {
+  fn to_native (&self) -> c_int {
+    match *self {
+      PropertyMode::Replace => ::ffi::PropModeReplace,
+      PropertyMode::Prepend => ::ffi::PropModePrepend,
+      PropertyMode::Append => ::ffi::PropModeAppend,
+    }
+  }
+}
+
+
+//
+// PropertyWatch
+//
+
+
+/** Filters PropertyNotify events for a set of properties on one window. Created by `Display::watch_properties`. */
+#[derive(Clone)]
+pub struct PropertyWatch {
+  window: Window,
+  atoms: Vec<Atom>,
+}
+
+impl PropertyWatch {
+  pub fn new (window: Window, atoms: &[Atom]) -> PropertyWatch {
+    PropertyWatch {
+      window: window,
+      atoms: atoms.to_vec(),
+    }
+  }
+
+  pub fn atoms (&self) -> &[Atom] {
+    &self.atoms
+  }
+
+  /** Returns the property event if `event` reports a change to one of the watched properties. */
+  pub fn matches<'a> (&self, event: &'a Event) -> Option<&'a PropertyEvent> {
+    if let Event::Property(ref e) = *event {
+      if e.window == self.window && self.atoms.contains(&e.atom) {
+        return Some(e);
+      }
+    }
+    return None;
+  }
+
+  pub fn window (&self) -> Window {
+    self.window
+  }
+}
+
+
+//
+// TextEncoding
+//
+
+
+/** How text is encoded when stored in a text property. `StdIccText` uses STRING when the text fits in Latin-1 and
+ * COMPOUND_TEXT otherwise, which is what ICCCM window managers expect for WM_NAME. */
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub enum TextEncoding {
+  String,
+  CompoundText,
+  Text,
+  StdIccText,
+  Utf8String,
+}
+
+impl ToNative<c_int> for TextEncoding {
+  fn to_native (&self) -> c_int {
+    match *self {
+      TextEncoding::String => ::ffi::XStringStyle,
+      TextEncoding::CompoundText => ::ffi::XCompoundTextStyle,
+      TextEncoding::Text => ::ffi::XTextStyle,
+      TextEncoding::StdIccText => ::ffi::XStdICCTextStyle,
+      TextEncoding::Utf8String => ::ffi::XUTF8StringStyle,
+    }
+  }
+}
@@ -1,10 +1,29 @@
 // Copyright (c) 2015, <daggerbot@gmail.com>
 // All rights reserved.
 
+use std::marker::PhantomData;
+use std::ptr::null_mut;
+
+use libc::{
+  c_int,
+  c_void,
+};
+
+use ::colormap::Colormap;
+use ::display::{
+  Display,
+  Xid,
+};
+use ::gc::Gcid;
 use ::internal::{
   FromNative,
   ToNative,
 };
+use ::visual::Visual;
+use ::window::{
+  BackingStore,
+  Window,
+};
 
 
 //
@@ -12,21 +31,148 @@ use ::internal::{
 //
 
 
+/** A screen of an open display. Borrows the display so it can't outlive the connection. */
 #[allow(raw_pointer_derive)]
 #[derive(Clone, Copy, Eq, Hash, PartialEq)]
-pub struct Screen {
+pub struct Screen<'a> {
   ptr: *const ::ffi::Screen,
+  _display: PhantomData<&'a Display>,
+}
+
+impl<'a> Screen<'a> {
+  /** Depths supported by the screen, including the root depth. */
+  pub fn allowed_depths (&self) -> Vec<i32> {
+    unsafe {
+      let mut depth_vec = Vec::new();
+      let mut count = 0;
+      let display = ::ffi::XDisplayOfScreen(self.ptr);
+      let depths_ptr = ::ffi::XListDepths(display, self.screen_number() as c_int, &mut count);
+      if depths_ptr == null_mut() {
+        return depth_vec;
+      }
+      let depths_const_ptr = depths_ptr as *const c_int;
+      let depths_slice = ::std::slice::from_raw_buf(&depths_const_ptr, count as usize);
+      for depth in depths_slice.iter() {
+        depth_vec.push(*depth as i32);
+      }
+      ::ffi::XFree(depths_ptr as *mut c_void);
+      return depth_vec;
+    }
+  }
+
+  pub fn backing_store (&self) -> BackingStore {
+    unsafe {
+      if let Some(bs) = FromNative::from_native(::ffi::XDoesBackingStore(self.ptr)) {
+        return bs;
+      } else {
+        error!("XDoesBackingStore returned invalid data");
+        return BackingStore::NotUseful;
+      }
+    }
+  }
+
+  pub fn black_pixel (&self) -> u32 {
+    unsafe {
+      return ::ffi::XBlackPixelOfScreen(self.ptr) as u32;
+    }
+  }
+
+  pub fn default_colormap (&self) -> Colormap {
+    unsafe {
+      return Colormap(::ffi::XDefaultColormapOfScreen(self.ptr) as Xid);
+    }
+  }
+
+  /** The id of the screen's default graphics context. It is shared, so it must not be freed or changed. */
+  pub fn default_gc (&self) -> Gcid {
+    unsafe {
+      return Gcid(::ffi::XGContextFromGC(::ffi::XDefaultGCOfScreen(self.ptr)) as Xid);
+    }
+  }
+
+  pub fn height (&self) -> i32 {
+    unsafe {
+      return ::ffi::XHeightOfScreen(self.ptr) as i32;
+    }
+  }
+
+  pub fn height_mm (&self) -> i32 {
+    unsafe {
+      return ::ffi::XHeightMMOfScreen(self.ptr) as i32;
+    }
+  }
+
+  pub fn max_installed_colormaps (&self) -> i32 {
+    unsafe {
+      return ::ffi::XMaxCmapsOfScreen(self.ptr) as i32;
+    }
+  }
+
+  pub fn min_installed_colormaps (&self) -> i32 {
+    unsafe {
+      return ::ffi::XMinCmapsOfScreen(self.ptr) as i32;
+    }
+  }
+
+  pub fn root_depth (&self) -> i32 {
+    unsafe {
+      return ::ffi::XDefaultDepthOfScreen(self.ptr) as i32;
+    }
+  }
+
+  pub fn root_visual (&self) -> Visual {
+    unsafe {
+      return FromNative::from_native(::ffi::XDefaultVisualOfScreen(self.ptr));
+    }
+  }
+
+  pub fn root_window (&self) -> Window {
+    unsafe {
+      return Window(::ffi::XRootWindowOfScreen(self.ptr) as Xid);
+    }
+  }
+
+  pub fn save_unders (&self) -> bool {
+    unsafe {
+      return ::ffi::XDoesSaveUnders(self.ptr) != 0;
+    }
+  }
+
+  pub fn screen_number (&self) -> i32 {
+    unsafe {
+      return ::ffi::XScreenNumberOfScreen(self.ptr) as i32;
+    }
+  }
+
+  pub fn white_pixel (&self) -> u32 {
+    unsafe {
+      return ::ffi::XWhitePixelOfScreen(self.ptr) as u32;
+    }
+  }
+
+  pub fn width (&self) -> i32 {
+    unsafe {
+      return ::ffi::XWidthOfScreen(self.ptr) as i32;
+    }
+  }
+
+  pub fn width_mm (&self) -> i32 {
+    unsafe {
+      return ::ffi::XWidthMMOfScreen(self.ptr) as i32;
+    }
+  }
 }
 
-impl FromNative<*const ::ffi::Screen> for Screen {
-  fn from_native (ptr: *const ::ffi::Screen) -> Screen {
+impl<'a> FromNative<*const ::ffi::Screen> for Screen<'a> {
+  fn from_native (ptr: *const ::ffi::Screen) -> Screen<'a> {
     Screen {
       ptr: ptr,
+      _display: PhantomData,
     }
   }
 }
 
-impl ToNative<*const ::ffi::Screen> for Screen {
+impl<'a> ToNative<*const ::ffi::Screen> for Screen<'a> {
   fn to_native (&self) -> *const ::ffi::Screen {
     self.ptr
   }
@@ -0,0 +1,74 @@
+// Copyright (c) 2015, <daggerbot@gmail.com>
+// All rights reserved.
+
+use std::io;
+use std::os::unix::io::RawFd;
+use std::pin::Pin;
+use std::task::{
+  Context,
+  Poll,
+};
+
+use futures_core::Stream;
+use tokio::io::unix::AsyncFd;
+
+use ::display::Display;
+use ::error::IoError;
+use ::event::Event;
+
+
+//
+// EventStream
+//
+
+
+/** Exposes X events as a `Stream`, woken by readiness of the connection file descriptor. Must be used from
+ * within a Tokio runtime. Events queued by other `Display` calls are picked up on the next poll. The stream ends
+ * after yielding an error, since the connection can't be used any further. */
+pub struct EventStream<'a> {
+  display: &'a mut Display,
+  fd: AsyncFd<RawFd>,
+  done: bool,
+}
+
+impl<'a> EventStream<'a> {
+  pub fn new (display: &'a mut Display) -> io::Result<EventStream<'a>> {
+    let fd = try!(AsyncFd::new(display.connection_number() as RawFd));
+    let stream = EventStream {
+      display: display,
+      fd: fd,
+      done: false,
+    };
+    return Ok(stream);
+  }
+}
+
+impl<'a> Stream for EventStream<'a> {
+  type Item = Result<Event, IoError>;
+
+  fn poll_next (self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Result<Event, IoError>>> {
+    let stream = self.get_mut();
+    if stream.done {
+      return Poll::Ready(None);
+    }
+    loop {
+      // XPending also reads whatever is waiting on the socket into Xlib's queue
+      match stream.display.poll_event() {
+        Ok(Some(event)) => return Poll::Ready(Some(Ok(event))),
+        Ok(None) => {},
+        Err(error) => {
+          stream.done = true;
+          return Poll::Ready(Some(Err(error)));
+        },
+      }
+      match stream.fd.poll_read_ready(cx) {
+        Poll::Ready(Ok(mut guard)) => guard.clear_ready(),
+        Poll::Ready(Err(error)) => {
+          stream.done = true;
+          return Poll::Ready(Some(Err(IoError { errno: error.raw_os_error().unwrap_or(0) })));
+        },
+        Poll::Pending => return Poll::Pending,
+      }
+    }
+  }
+}
@@ -1,25 +1,42 @@
 // Copyright (c) 2015, <daggerbot@gmail.com>
 // All rights reserved.
 
+use std::panic::{
+  catch_unwind,
+  AssertUnwindSafe,
+};
+use std::sync::{
+  Arc,
+  Mutex,
+  MutexGuard,
+};
+
 use ::display::{
+  reset_error_handler,
+  set_error_handler,
   Atom,
   Display,
 };
+use ::error::ErrorCode;
 use ::event::{
   ClientMessageData,
   Event,
 };
+use ::window::Window;
 
 
+// Error handlers are process-wide, so tests that open a display must not run in parallel.
+static DISPLAY_TESTS: Mutex<()> = Mutex::new(());
+
 #[test]
 fn test () {
+  let _guard = lock_display_tests();
+
   // open display
-  let mut display;
-  if let Some(d) = Display::open_default() {
-    display = d;
-  } else {
-    panic!("can't open display");
-  }
+  let mut display = match Display::open_default() {
+    Ok(d) => d,
+    Err(e) => panic!("can't open display: {}", e),
+  };
 
   // get atoms
   let atom_wm_delete_window = force_intern_atom(&mut display, "WM_DELETE_WINDOW");
@@ -38,20 +55,58 @@ fn test () {
   // main loop
   loop {
     match display.next_event() {
-      Event::ClientMessage(e) => {
+      Ok(Event::ClientMessage(e)) => {
         if e.message_type == atom_wm_protocols {
           if let ClientMessageData::Long(data) = e.data {
-            if data[0] == atom_wm_delete_window {
+            if data[0] == atom_wm_delete_window.0 {
               break;
             }
           }
         }
       },
-      _ => {},
+      Ok(_) => {},
+      Err(e) => panic!("{}", e),
     }
   }
 }
 
+#[test]
+fn trap_errors_after_panic () {
+  let _guard = lock_display_tests();
+  let mut display = match Display::open_default() {
+    Ok(d) => d,
+    Err(e) => panic!("can't open display: {}", e),
+  };
+  let handled = Arc::new(Mutex::new(Vec::new()));
+  let handled_ref = handled.clone();
+  set_error_handler(move |error| handled_ref.lock().unwrap().push(error.code));
+
+  // a panicking closure must not leave its trap behind
+  let result = catch_unwind(AssertUnwindSafe(|| {
+    display.trap_errors(|d| {
+      d.destroy_window(Window(0));
+      panic!("closure panicked");
+    })
+  }));
+  assert!(result.is_err());
+  display.sync(false);
+  handled.lock().unwrap().clear();
+
+  // trapped error
+  match display.trap_errors(|d| d.destroy_window(Window(0))) {
+    Err(error) => assert!(error.code == ErrorCode::BadWindow),
+    Ok(_) => panic!("trapped error was not reported"),
+  }
+  assert!(handled.lock().unwrap().is_empty());
+
+  // untrapped error
+  display.destroy_window(Window(0));
+  display.sync(false);
+  assert!(*handled.lock().unwrap() == vec![ErrorCode::BadWindow]);
+
+  reset_error_handler();
+}
+
 fn force_intern_atom (display: &mut Display, name: &str) -> Atom {
   if let Some(atom) = display.intern_atom(name, false) {
     return atom;
@@ -59,3 +114,7 @@ fn force_intern_atom (display: &mut Display, name: &str) -> Atom {
     panic!("failed to retrieve atom: {}", name);
   }
 }
+
+fn lock_display_tests () -> MutexGuard<'static, ()> {
+  DISPLAY_TESTS.lock().unwrap_or_else(|e| e.into_inner())
+}
@@ -10,19 +10,33 @@ use libc::{
 
 use ::colormap::Colormap;
 use ::cursor::Cursor;
-use ::display::Xid;
+use ::display::{
+  Connection,
+  Xid,
+};
+use ::drawable::Drawable;
 use ::event::EventMask;
 use ::internal::{
   FieldMask,
   FromNative,
   ToNative,
 };
+use ::owned::{
+  Owned,
+  Resource,
+};
 use ::pixmap::Pixmap;
-use ::screen::Screen;
 use ::visual::Visual;
 
 /** Window identifier type. */
-pub type Window = Xid;
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Window(pub Xid);
+
+impl Drawable for Window {
+  fn drawable_id (&self) -> Xid {
+    self.0
+  }
+}
 
 
 //
@@ -73,6 +87,40 @@ impl ToNative<c_int> for BackingStore {
 }
 
 
+//
+// CirculateDirection
+//
+
+
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub enum CirculateDirection {
+  RaiseLowest,
+  LowerHighest,
+}
+
+impl ToNative<c_int> for CirculateDirection {
+  fn to_native (&self) -> c_int {
+    match *self {
+      CirculateDirection::RaiseLowest => 0,
+      CirculateDirection::LowerHighest => 1,
+    }
+  }
+}
+
+
+//
+// ClassHint
+//
+
+
+/** WM_CLASS contents, used by window managers to match windows against rules. */
+#[derive(Clone, Eq, Hash, PartialEq)]
+pub struct ClassHint {
+  pub instance: String,
+  pub class: String,
+}
+
+
 //
 // Gravity
 //
@@ -131,6 +179,40 @@ impl ToNative<c_int> for Gravity {
 }
 
 
+//
+// InitialState
+//
+
+
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub enum InitialState {
+  Withdrawn,
+  Normal,
+  Iconic,
+}
+
+impl FromNative<c_int> for Option<InitialState> {
+  fn from_native (num: c_int) -> Option<InitialState> {
+    match num {
+      0 => Some(InitialState::Withdrawn),
+      1 => Some(InitialState::Normal),
+      3 => Some(InitialState::Iconic),
+      _ => None,
+    }
+  }
+}
+
+impl ToNative<c_int> for InitialState {
+  fn to_native (&self) -> c_int {
+    match *self {
+      InitialState::Withdrawn => 0,
+      InitialState::Normal => 1,
+      InitialState::Iconic => 3,
+    }
+  }
+}
+
+
 //
 // MapState
 //
@@ -155,6 +237,23 @@ impl FromNative<c_int> for Option<MapState> {
 }
 
 
+//
+// OwnedWindow
+//
+
+
+/** A window that is destroyed when dropped. */
+pub type OwnedWindow = Owned<Window>;
+
+impl Resource for Window {
+  fn free (self, connection: &Connection) {
+    unsafe {
+      ::ffi::XDestroyWindow(connection.to_native(), self.0 as c_ulong);
+    }
+  }
+}
+
+
 //
 // SetWindowAttributes
 //
@@ -204,9 +303,9 @@ impl FieldMask<c_ulong> for SetWindowAttributes {
 impl ToNative<::ffi::XSetWindowAttributes> for SetWindowAttributes {
   fn to_native (&self) -> ::ffi::XSetWindowAttributes {
     ::ffi::XSetWindowAttributes {
-      background_pixmap: if let Some(p) = self.background_pixmap {p as c_ulong} else {0},
+      background_pixmap: if let Some(p) = self.background_pixmap {p.0 as c_ulong} else {0},
       background_pixel: if let Some(p) = self.background_pixel {p as c_ulong} else {0},
-      border_pixmap: if let Some(p) = self.border_pixmap {p as c_ulong} else {0},
+      border_pixmap: if let Some(p) = self.border_pixmap {p.0 as c_ulong} else {0},
       border_pixel: if let Some(p) = self.border_pixel {p as c_ulong} else {0},
       bit_gravity: if let Some(g) = self.bit_gravity {g.to_native()} else {0},
       win_gravity: if let Some(g) = self.win_gravity {g.to_native()} else {0},
@@ -217,8 +316,8 @@ impl ToNative<::ffi::XSetWindowAttributes> for SetWindowAttributes {
       event_mask: if let Some(em) = self.event_mask {em.to_native()} else {0},
       do_not_propagate_mask: if let Some(em) = self.do_not_propagate_mask {em.to_native()} else {0},
       override_redirect: if let Some(b) = self.override_redirect {if b {1} else {0}} else {0},
-      colormap: if let Some(c) = self.colormap {c as c_ulong} else {0},
-      cursor: if let Some(c) = self.cursor {c as c_ulong} else {0},
+      colormap: if let Some(c) = self.colormap {c.0 as c_ulong} else {0},
+      cursor: if let Some(c) = self.cursor {c.0 as c_ulong} else {0},
     }
   }
 }
@@ -256,6 +355,32 @@ impl FieldMask<c_long> for SizeHints {
   }
 }
 
+// The user-specified and program-specified position and size flags are both accepted.
+impl FromNative<::ffi::XSizeHints> for SizeHints {
+  fn from_native (xhints: ::ffi::XSizeHints) -> SizeHints {
+    let flags = xhints.flags;
+    SizeHints {
+      position: if flags & 0x0005 != 0 {Some((xhints.x as i32, xhints.y as i32))} else {None},
+      size: if flags & 0x000a != 0 {Some((xhints.width as i32, xhints.height as i32))} else {None},
+      min_size: if flags & 0x0010 != 0 {Some((xhints.min_width as i32, xhints.min_height as i32))} else {None},
+      max_size: if flags & 0x0020 != 0 {Some((xhints.max_width as i32, xhints.max_height as i32))} else {None},
+      resize_inc: if flags & 0x0040 != 0 {Some((xhints.width_inc as i32, xhints.height_inc as i32))} else {None},
+      aspect: if flags & 0x0080 != 0 {
+        Some(AspectRatioHint {
+          min_numerator: xhints.min_aspect.x as i32,
+          min_denominator: xhints.min_aspect.y as i32,
+          max_numerator: xhints.max_aspect.x as i32,
+          max_denominator: xhints.max_aspect.y as i32,
+        })
+      } else {
+        None
+      },
+      base_size: if flags & 0x0100 != 0 {Some((xhints.base_width as i32, xhints.base_height as i32))} else {None},
+      win_gravity: if flags & 0x0200 != 0 {FromNative::from_native(xhints.win_gravity)} else {None},
+    }
+  }
+}
+
 impl ToNative<::ffi::XSizeHints> for SizeHints {
   fn to_native (&self) -> ::ffi::XSizeHints {
     ::ffi::XSizeHints {
@@ -282,6 +407,71 @@ impl ToNative<::ffi::XSizeHints> for SizeHints {
 }
 
 
+//
+// SizeHintsMask
+//
+
+
+/** The WM_NORMAL_HINTS fields a client supplied, as reported by `Display::get_wm_normal_hints`. */
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub struct SizeHintsMask {
+  pub user_position: bool,
+  pub user_size: bool,
+  pub program_position: bool,
+  pub program_size: bool,
+  pub min_size: bool,
+  pub max_size: bool,
+  pub resize_inc: bool,
+  pub aspect: bool,
+  pub base_size: bool,
+  pub win_gravity: bool,
+}
+
+impl FromNative<c_long> for SizeHintsMask {
+  fn from_native (mask: c_long) -> SizeHintsMask {
+    SizeHintsMask {
+      user_position: mask & 0x0001 != 0,
+      user_size: mask & 0x0002 != 0,
+      program_position: mask & 0x0004 != 0,
+      program_size: mask & 0x0008 != 0,
+      min_size: mask & 0x0010 != 0,
+      max_size: mask & 0x0020 != 0,
+      resize_inc: mask & 0x0040 != 0,
+      aspect: mask & 0x0080 != 0,
+      base_size: mask & 0x0100 != 0,
+      win_gravity: mask & 0x0200 != 0,
+    }
+  }
+}
+
+
+//
+// StackMode
+//
+
+
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub enum StackMode {
+  Above,
+  Below,
+  TopIf,
+  BottomIf,
+  Opposite,
+}
+
+impl ToNative<c_int> for StackMode {
+  fn to_native (&self) -> c_int {
+    match *self {
+      StackMode::Above => 0,
+      StackMode::Below => 1,
+      StackMode::TopIf => 2,
+      StackMode::BottomIf => 3,
+      StackMode::Opposite => 4,
+    }
+  }
+}
+
+
 //
 // WindowAttributes
 //
@@ -311,7 +501,8 @@ pub struct WindowAttributes {
   pub your_event_mask: EventMask,
   pub do_not_propagate_mask: EventMask,
   pub override_redirect: bool,
-  pub screen: Screen,
+  /** Number of the screen the window is on. Pass it to `Display::screen` for a `Screen`. */
+  pub screen: i32,
 }
 
 impl FromNative<::ffi::XWindowAttributes> for Option<WindowAttributes> {
@@ -324,7 +515,7 @@ impl FromNative<::ffi::XWindowAttributes> for Option<WindowAttributes> {
       border_width: xattr.border_width as i32,
       depth: xattr.depth as i32,
       visual: FromNative::from_native(xattr.visual),
-      root: xattr.root as Window,
+      root: Window(xattr.root as Xid),
       class: if let Some(c) = FromNative::from_native(xattr.class) {c} else { return None; },
       bit_gravity: if let Some(g) = FromNative::from_native(xattr.bit_gravity) {g} else { return None; },
       win_gravity: if let Some(g) = FromNative::from_native(xattr.win_gravity) {g} else { return None; },
@@ -332,20 +523,66 @@ impl FromNative<::ffi::XWindowAttributes> for Option<WindowAttributes> {
       backing_planes: xattr.backing_planes as u32,
       backing_pixel: xattr.backing_pixel as u32,
       save_under: xattr.save_under != 0,
-      colormap: xattr.colormap as Colormap,
+      colormap: Colormap(xattr.colormap as Xid),
       map_installed: xattr.map_installed != 0,
       map_state: if let Some(ms) = FromNative::from_native(xattr.map_state) {ms} else { return None; },
       all_event_masks: FromNative::from_native(xattr.all_event_masks),
       your_event_mask: FromNative::from_native(xattr.your_event_mask),
       do_not_propagate_mask: FromNative::from_native(xattr.do_not_propagate_mask),
       override_redirect: xattr.override_redirect != 0,
-      screen: FromNative::from_native(xattr.screen),
+      screen: unsafe { ::ffi::XScreenNumberOfScreen(xattr.screen) as i32 },
     };
     return Some(attr);
   }
 }
 
 
+//
+// WindowChanges
+//
+
+
+/** Changes applied by `Display::configure_window`. If `sibling` is set, `stack_mode` must be set too. */
+#[derive(Clone, Copy, Default)]
+pub struct WindowChanges {
+  pub x: Option<i32>,
+  pub y: Option<i32>,
+  pub width: Option<i32>,
+  pub height: Option<i32>,
+  pub border_width: Option<i32>,
+  pub sibling: Option<Window>,
+  pub stack_mode: Option<StackMode>,
+}
+
+impl FieldMask<c_uint> for WindowChanges {
+  fn field_mask (&self) -> c_uint {
+    let mut mask: c_uint = 0;
+    if let Some(_) = self.x { mask |= 0x0001; }
+    if let Some(_) = self.y { mask |= 0x0002; }
+    if let Some(_) = self.width { mask |= 0x0004; }
+    if let Some(_) = self.height { mask |= 0x0008; }
+    if let Some(_) = self.border_width { mask |= 0x0010; }
+    if let Some(_) = self.sibling { mask |= 0x0020; }
+    if let Some(_) = self.stack_mode { mask |= 0x0040; }
+    return mask;
+  }
+}
+
+impl ToNative<::ffi::XWindowChanges> for WindowChanges {
+  fn to_native (&self) -> ::ffi::XWindowChanges {
+    ::ffi::XWindowChanges {
+      x: if let Some(x) = self.x {x as c_int} else {0},
+      y: if let Some(y) = self.y {y as c_int} else {0},
+      width: if let Some(w) = self.width {w as c_int} else {0},
+      height: if let Some(h) = self.height {h as c_int} else {0},
+      border_width: if let Some(bw) = self.border_width {bw as c_int} else {0},
+      sibling: if let Some(s) = self.sibling {s.0 as c_ulong} else {0},
+      stack_mode: if let Some(sm) = self.stack_mode {sm.to_native()} else {0},
+    }
+  }
+}
+
+
 //
 // WindowClass
 //
@@ -377,6 +614,86 @@ impl ToNative<c_uint> for WindowClass {
 }
 
 
+//
+// WindowTree
+//
+
+
+/** Result of `Display::query_tree`. Children are listed in stacking order, bottom-most first. */
+#[derive(Clone)]
+pub struct WindowTree {
+  pub root: Window,
+  pub parent: Option<Window>,
+  pub children: Vec<Window>,
+}
+
+
+//
+// WmHints
+//
+
+
+#[derive(Clone, Copy)]
+pub struct WmHints {
+  pub input: Option<bool>,
+  pub initial_state: Option<InitialState>,
+  pub icon_pixmap: Option<Pixmap>,
+  pub icon_window: Option<Window>,
+  pub icon_position: Option<(i32, i32)>,
+  pub icon_mask: Option<Pixmap>,
+  pub window_group: Option<Window>,
+  pub urgent: bool,
+}
+
+impl FieldMask<c_long> for WmHints {
+  fn field_mask (&self) -> c_long {
+    let mut mask: c_long = 0;
+    if let Some(_) = self.input { mask |= 0x0001; }
+    if let Some(_) = self.initial_state { mask |= 0x0002; }
+    if let Some(_) = self.icon_pixmap { mask |= 0x0004; }
+    if let Some(_) = self.icon_window { mask |= 0x0008; }
+    if let Some(_) = self.icon_position { mask |= 0x0010; }
+    if let Some(_) = self.icon_mask { mask |= 0x0020; }
+    if let Some(_) = self.window_group { mask |= 0x0040; }
+    if self.urgent { mask |= 0x0100; }
+    return mask;
+  }
+}
+
+// Fields whose flag is set but whose value is invalid are dropped.
+impl FromNative<::ffi::XWMHints> for WmHints {
+  fn from_native (xhints: ::ffi::XWMHints) -> WmHints {
+    let flags = xhints.flags;
+    WmHints {
+      input: if flags & 0x0001 != 0 {Some(xhints.input != 0)} else {None},
+      initial_state: if flags & 0x0002 != 0 {FromNative::from_native(xhints.initial_state)} else {None},
+      icon_pixmap: if flags & 0x0004 != 0 {Some(Pixmap(xhints.icon_pixmap as Xid))} else {None},
+      icon_window: if flags & 0x0008 != 0 {Some(Window(xhints.icon_window as Xid))} else {None},
+      icon_position: if flags & 0x0010 != 0 {Some((xhints.icon_x as i32, xhints.icon_y as i32))} else {None},
+      icon_mask: if flags & 0x0020 != 0 {Some(Pixmap(xhints.icon_mask as Xid))} else {None},
+      window_group: if flags & 0x0040 != 0 {Some(Window(xhints.window_group as Xid))} else {None},
+      urgent: flags & 0x0100 != 0,
+    }
+  }
+}
+
+impl ToNative<::ffi::XWMHints> for WmHints {
+  fn to_native (&self) -> ::ffi::XWMHints {
+    ::ffi::XWMHints {
+      flags: self.field_mask(),
+      input: if let Some(true) = self.input {1} else {0},
+      initial_state: if let Some(s) = self.initial_state {s.to_native()} else {0},
+      icon_pixmap: if let Some(p) = self.icon_pixmap {p.0 as c_ulong} else {0},
+      icon_window: if let Some(w) = self.icon_window {w.0 as c_ulong} else {0},
+      icon_x: if let Some((x, _)) = self.icon_position {x as c_int} else {0},
+      icon_y: if let Some((_, y)) = self.icon_position {y as c_int} else {0},
+      icon_mask: if let Some(p) = self.icon_mask {p.0 as c_ulong} else {0},
+      window_group: if let Some(w) = self.window_group {w.0 as c_ulong} else {0},
+    }
+  }
+}
+
+
 //
 // public functions
 //
@@ -387,3 +704,70 @@ pub fn all_planes () -> u32 {
     return ::ffi::XAllPlanes() as u32;
   }
 }
+
+
+#[cfg(test)]
+mod test {
+  use libc::c_long;
+
+  use ::internal::{
+    FieldMask,
+    FromNative,
+    ToNative,
+  };
+
+  use super::{
+    SizeHints,
+    SizeHintsMask,
+    StackMode,
+    Window,
+    WindowChanges,
+  };
+
+  #[test]
+  fn size_hints_mask () {
+    let mask: SizeHintsMask = FromNative::from_native(0x0001 | 0x0008 | 0x0200);
+    assert!(mask.user_position && mask.program_size && mask.win_gravity);
+    assert!(!mask.user_size && !mask.program_position && !mask.min_size && !mask.max_size);
+    assert!(!mask.resize_inc && !mask.aspect && !mask.base_size);
+
+    let all: SizeHintsMask = FromNative::from_native(0x03ff);
+    assert!(all.user_position && all.user_size && all.program_position && all.program_size && all.min_size);
+    assert!(all.max_size && all.resize_inc && all.aspect && all.base_size && all.win_gravity);
+  }
+
+  #[test]
+  fn size_hints_accept_user_and_program_flags () {
+    let mut hints = SizeHints::default();
+    hints.position = Some((10, 20));
+    hints.size = Some((300, 200));
+    let mut xhints = hints.to_native();
+    assert_eq!(xhints.flags, 0x0004 | 0x0008);
+
+    // user-specified position and size decode the same way
+    xhints.flags = 0x0001 | 0x0002;
+    let decoded: SizeHints = FromNative::from_native(xhints);
+    assert_eq!(decoded.position, Some((10, 20)));
+    assert_eq!(decoded.size, Some((300, 200)));
+    assert_eq!(decoded.field_mask(), 0x0004 as c_long | 0x0008);
+  }
+
+  #[test]
+  fn window_changes_field_mask () {
+    assert_eq!(WindowChanges::default().field_mask(), 0);
+
+    let mut changes = WindowChanges::default();
+    changes.x = Some(-5);
+    changes.sibling = Some(Window(42));
+    changes.stack_mode = Some(StackMode::Below);
+    assert_eq!(changes.field_mask(), 0x0001 | 0x0020 | 0x0040);
+    let xchanges = changes.to_native();
+    assert_eq!((xchanges.x, xchanges.sibling, xchanges.stack_mode), (-5, 42, 1));
+
+    changes.y = Some(0);
+    changes.width = Some(1);
+    changes.height = Some(1);
+    changes.border_width = Some(0);
+    assert_eq!(changes.field_mask(), 0x007f);
+  }
+}